@@ -0,0 +1,34 @@
+use std::{env, fs, io, path::Path};
+
+// 把 glsl 文件夹中的着色器以 include_str! 的形式嵌入，生成 (相对路径, 内容) 表
+fn main() -> io::Result<()> {
+    let root = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let glsl = Path::new(&root).join("glsl");
+    println!("cargo:rerun-if-changed=glsl");
+
+    let mut files = Vec::new();
+    if glsl.is_dir() { collect(&glsl, &mut files)?; }
+    files.sort();
+
+    let mut code = String::from("pub static EMBEDDED_SHADERS: &[(&str, &str)] = &[\n");
+    for file in &files {
+        let relative = file.strip_prefix(&root).unwrap().to_string_lossy().replace('\\', "/");
+        code.push_str(&format!("    ({:?}, include_str!({:?})),\n", relative, file.to_string_lossy()));
+    }
+    code.push_str("];\n");
+
+    fs::write(Path::new(&env::var("OUT_DIR").unwrap()).join("embedded_shaders.rs"), code)
+}
+
+fn collect(dir: &Path, files: &mut Vec<std::path::PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            println!("cargo:rerun-if-changed={}", path.display());
+            collect(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
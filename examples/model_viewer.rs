@@ -0,0 +1,137 @@
+#![allow(dead_code)]
+#![allow(non_snake_case)]
+#![allow(clippy::missing_safety_doc)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use opengl_rs::IRenderer;
+use opengl_rs::base::camera::Camera;
+use opengl_rs::base::engine::Engine;
+use opengl_rs::base::error::GLError;
+
+use nalgebra_glm as glm;
+use opengl_rs::base::model::{Model, MaterialType};
+use opengl_rs::base::program::ShaderProgram;
+use opengl_rs::base::shader_variant::ShaderVariants;
+use opengl_rs::base::picking::PickingPass;
+use glfw::{Action, Modifiers, MouseButton};
+
+const WINDOW_TITLE: &str = "model_viewer";
+const WINDOW_SIZE: (u32, u32) = (1200, 1200);
+
+const VERTEX_SOURCE_FILE: &str = "glsl/model/model.vs";
+const FRAGMENT_SOURCE_FILE: &str = "glsl/model/model.fs";
+
+const MODEL_FILE: &str = "assets/model/monkey/monkey.obj";
+
+pub struct ModelViewer {
+    camera: Rc<RefCell<Camera>>,
+    variants: ShaderVariants,                   // 根据网格的纹理选择着色器变体
+    model: Model,
+    picking: PickingPass,
+    cursor: (f32, f32),
+    press_pos: Option<(f32, f32)>,              // 左键按下时的光标位置，松开时位置不变则视为点击
+}
+
+impl ModelViewer {
+    pub unsafe fn new() -> Result<Self, GLError> {
+        // 环绕模式：左键拖拽旋转，右键拖拽平移，滚轮缩放
+        let camera = Rc::new(RefCell::new(Camera::new_orbit(glm::vec3(0.0, 0.0, 0.0), 4.0)));
+
+        let variants = ShaderVariants::new(VERTEX_SOURCE_FILE, FRAGMENT_SOURCE_FILE);
+        let model = Model::new(MODEL_FILE, Some(&[MaterialType::Diffuse, MaterialType::Specular, MaterialType::Normal]))?;
+
+        let picking = PickingPass::new(WINDOW_SIZE)?;
+
+        gl::Enable(gl::DEPTH_TEST);
+
+        Ok(Self { camera, variants, model, picking, cursor: (0.0, 0.0), press_pos: None })
+    }
+
+    // 点击拾取，打印光标下的网格
+    unsafe fn pick(&self) -> Result<(), GLError> {
+        self.picking.begin(&self.camera.borrow())?;
+        self.picking.draw_model(&self.model, &glm::Mat4::identity(), 1)?;
+        self.picking.end();
+
+        match self.picking.read((self.cursor.0 as f64, self.cursor.1 as f64)) {
+            Some(hit) => println!("picked mesh {}", hit.mesh),
+            None => println!("picked nothing"),
+        }
+
+        Ok(())
+    }
+}
+
+impl IRenderer for ModelViewer {
+    // 绘制
+    unsafe fn draw(&self) -> Result<(), GLError> {
+        self.clear();
+
+        let camera = self.camera.borrow();
+
+        let model = glm::Mat4::identity();
+        self.model.draw_variants_culled(&self.variants, &camera.get_frustum(), &model, |program: &ShaderProgram| {
+            program.set_vec3("objectColor", &[1.0, 0.5, 0.31])?;
+            program.set_vec3("lightColor", &[1.0, 1.0, 1.0])?;
+            // 光源跟随摄像机
+            program.set_vec3("lightPos", glm::value_ptr(&camera.get_pos()))?;
+            program.set_vec3("viewPos", glm::value_ptr(&camera.get_pos()))?;
+
+            program.set_mat4("projection", glm::value_ptr(&camera.get_projection_matrix()))?;
+            program.set_mat4("view", glm::value_ptr(&camera.get_view_matrix()))?;
+            program.set_mat4("model", glm::value_ptr(&model))?;
+
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    // 清屏
+    unsafe fn clear(&self) {
+        gl::ClearColor(0.5, 0.5, 0.5, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+    }
+
+    // 着色器热重载
+    unsafe fn reload_shaders(&mut self) { self.variants.reload_if_changed(); }
+
+    // 获取摄像机
+    fn getCamera(&self) -> Option<Rc<RefCell<Camera>>> { Some(Rc::clone(&self.camera)) }
+
+    // 帧缓冲大小改变
+    fn on_resize(&mut self, size: (u32, u32)) {
+        if let Err(err) = unsafe { self.picking.resize(size) } {
+            eprintln!("{}", err);
+        }
+    }
+
+    // 鼠标按键事件
+    fn on_mouse_button(&mut self, button: MouseButton, action: Action, _mods: Modifiers) {
+        if button != MouseButton::Button1 { return; }
+
+        match action {
+            Action::Press => self.press_pos = Some(self.cursor),
+            Action::Release if self.press_pos.take() == Some(self.cursor) => {
+                if let Err(err) = unsafe { self.pick() } {
+                    eprintln!("{}", err);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    // 鼠标移动事件
+    fn on_cursor_move(&mut self, x: f32, y: f32) { self.cursor = (x, y); }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let act = || -> Result<ModelViewer, GLError> {unsafe{ ModelViewer::new() }};
+
+    let mut engine = Engine::<ModelViewer>::new(WINDOW_TITLE, WINDOW_SIZE, act)?;
+    engine.execute()?;
+    
+    Ok(())
+}
@@ -60,7 +60,6 @@ impl IRenderer for PhoneLight {
     unsafe fn draw(&self) -> Result<(), GLError> {
         self.clear();
 
-        let win_radio = (WINDOW_SIZE.0 / WINDOW_SIZE.1) as f32;
         const LIGHT_POS: &[f32] = &[1.2, 1.0, 2.0];
 
         self.object_program.set_vec3("objectColor", &[1.0, 0.5, 0.31])?;
@@ -68,7 +67,7 @@ impl IRenderer for PhoneLight {
         self.object_program.set_vec3("lightPos", LIGHT_POS)?;
         self.object_program.set_vec3("viewPos", glm::value_ptr(&self.camera.borrow().get_pos()))?;
 
-        let projection = glm::perspective(win_radio, f32::to_radians(self.camera.borrow().get_fov()), 0.1, 100.0);
+        let projection = self.camera.borrow().get_projection_matrix();
         self.object_program.set_mat4("projection", glm::value_ptr(&projection))?;
 
         let view = self.camera.borrow().get_view_matrix();
@@ -108,6 +107,12 @@ impl IRenderer for PhoneLight {
         gl::Clear(gl::COLOR_BUFFER_BIT);
      }
 
+    // 着色器热重载
+    unsafe fn reload_shaders(&mut self) {
+        self.light_program.reload_if_changed();
+        self.object_program.reload_if_changed();
+    }
+
     // 获取摄像机
     fn getCamera(&self) -> Option<Rc<RefCell<Camera>>> { Some(Rc::clone(&self.camera)) }
 }
@@ -13,6 +13,7 @@ use opengl_rs::base::error::GLError;
 use nalgebra_glm as glm;
 use opengl_rs::base::model::Model;
 use opengl_rs::base::program::ShaderProgram;
+use opengl_rs::base::uniform::Uniforms;
 
 const WINDOW_TITLE: &str = "phone_light";
 const WINDOW_SIZE: (u32, u32) = (1200, 1200);
@@ -25,6 +26,24 @@ const OBJECT_FRAGMENT_SOURCE_FILE: &str = "glsl/phone_light_material/object.fs";
 const OBJECT_MODEL_FILE: &str = "assets/model/cube/cueb.obj";
 const LIGHT_MODEL_FILE: &str = "assets/model/sphere/sphere.obj";
 
+// 对应 glsl/include/lighting.glsl 中的 Light
+#[derive(Uniforms)]
+struct Light {
+    position: glm::Vec3,
+    ambient: glm::Vec3,                 // 环境光照的颜色强度
+    diffuse: glm::Vec3,                 // 漫反射光照的颜色强度
+    specular: glm::Vec3,                // 镜面光照的颜色强度
+}
+
+// 对应 glsl/include/lighting.glsl 中的 Material
+#[derive(Uniforms)]
+struct Material {
+    ambient: glm::Vec3,
+    diffuse: glm::Vec3,
+    specular: glm::Vec3,
+    shininess: f32,                     // 反光度
+}
+
 pub struct PhoneLight {
     camera: Rc<RefCell<Camera>>,
 
@@ -56,30 +75,28 @@ impl PhoneLight {
 }
 
 impl IRenderer for PhoneLight {
+    const USES_CAMERA_BLOCK: bool = true;
+
     // 绘制
     unsafe fn draw(&self) -> Result<(), GLError> {
         self.clear();
 
-        let win_radio = (WINDOW_SIZE.0 / WINDOW_SIZE.1) as f32;
-        const LIGHT_POS: &[f32] = &[1.2, 1.0, 2.0];
-
-        self.object_program.set_vec3("viewPos", glm::value_ptr(&self.camera.borrow().get_pos()))?;
-
-        self.object_program.set_vec3("light.position", LIGHT_POS)?;
-        self.object_program.set_vec3("light.ambient", &[0.2, 0.2, 0.2])?;
-        self.object_program.set_vec3("light.diffuse", &[0.5, 0.5, 0.5])?;
-        self.object_program.set_vec3("light.specular", &[1.0, 1.0, 1.0])?;
-
-        self.object_program.set_vec3("material.ambient", &[1.0, 0.5, 0.31])?;
-        self.object_program.set_vec3("material.diffuse", &[1.0, 0.5, 0.31])?;
-        self.object_program.set_vec3("material.specular", &[0.5, 0.5, 0.5])?;
-        self.object_program.set_float("material.shininess", 32.0)?;
-
-        let projection = glm::perspective(win_radio, f32::to_radians(self.camera.borrow().get_fov()), 0.1, 100.0);
-        self.object_program.set_mat4("projection", glm::value_ptr(&projection))?;
-
-        let view = self.camera.borrow().get_view_matrix();
-        self.object_program.set_mat4("view", glm::value_ptr(&view))?;
+        let light = Light {
+            position: glm::vec3(1.2, 1.0, 2.0),
+            ambient: glm::vec3(0.2, 0.2, 0.2),
+            diffuse: glm::vec3(0.5, 0.5, 0.5),
+            specular: glm::vec3(1.0, 1.0, 1.0),
+        };
+        let material = Material {
+            ambient: glm::vec3(1.0, 0.5, 0.31),
+            diffuse: glm::vec3(1.0, 0.5, 0.31),
+            specular: glm::vec3(0.5, 0.5, 0.5),
+            shininess: 32.0,
+        };
+
+        // view、projection、viewPos 由引擎通过摄像机 uniform 块统一上传
+        self.object_program.set_uniforms("light", &light)?;
+        self.object_program.set_uniforms("material", &material)?;
 
         let mut model = glm::Mat4::identity();
         model = glm::scale(&model, &glm::Vec3::new(0.6, 0.6, 0.6));
@@ -87,10 +104,8 @@ impl IRenderer for PhoneLight {
 
         self.object_model.draw(&self.object_program)?;
 
-        self.light_program.set_mat4("projection", glm::value_ptr(&projection))?;
-        self.light_program.set_mat4("view", glm::value_ptr(&view))?;
         let mut model = glm::Mat4::identity();
-        model = glm::translate(&model, &glm::Vec3::from_row_slice(LIGHT_POS));
+        model = glm::translate(&model, &light.position);
         model = glm::scale(&model, &glm::Vec3::new(0.2, 0.2, 0.2));
         self.light_program.set_mat4("model", glm::value_ptr(&model))?;
         
@@ -115,6 +130,12 @@ impl IRenderer for PhoneLight {
         gl::Clear(gl::COLOR_BUFFER_BIT);
      }
 
+    // 着色器热重载
+    unsafe fn reload_shaders(&mut self) {
+        self.light_program.reload_if_changed();
+        self.object_program.reload_if_changed();
+    }
+
     // 获取摄像机
     fn getCamera(&self) -> Option<Rc<RefCell<Camera>>> { Some(Rc::clone(&self.camera)) }
 }
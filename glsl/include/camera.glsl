@@ -0,0 +1,6 @@
+/* 摄像机数据，由引擎每帧更新一次，所有程序共享 */
+layout (std140) uniform Camera {
+    mat4 view;
+    mat4 projection;
+    vec3 viewPos;                                                           // 摄像机的位置向量
+};
@@ -0,0 +1,16 @@
+/* 定义材质属性 */
+struct Material {
+    vec3 ambient;                                                           // 在环境光照下，表面反射的颜色，通常与表面的颜色相同
+    vec3 diffuse;                                                           // 在漫反射光照下，表面的颜色
+    vec3 specular;                                                          // 镜面反射下，高光的颜色
+    float shininess;                                                        // 反光度，镜面高光的散射/半径
+};
+
+/* 定义光源属性 */
+struct Light {
+    vec3 position;
+
+    vec3 ambient;                                                           // 环境光照的颜色强度
+    vec3 diffuse;                                                           // 漫反射光照的颜色强度
+    vec3 specular;                                                          // 镜面光照的颜色强度
+};
@@ -0,0 +1,75 @@
+#version 330 core
+
+// 由 ShaderVariants 根据网格拥有的纹理定义：HAS_DIFFUSE_MAP、HAS_SPECULAR_MAP、HAS_NORMAL_MAP
+
+out vec4 FragColor;
+
+in vec3 FragPos;
+in vec3 Normal;
+in vec2 TexCoords;
+
+uniform vec3 objectColor;                                                   // 没有漫反射贴图时使用的颜色
+uniform vec3 viewPos;                                                       // 摄像机的位置向量
+uniform vec3 lightColor;
+uniform vec3 lightPos;                                                      // 光源的位置向量
+
+#ifdef HAS_DIFFUSE_MAP
+uniform sampler2D texture_diffuse1;
+#endif
+#ifdef HAS_SPECULAR_MAP
+uniform sampler2D texture_specular1;
+#endif
+#ifdef HAS_NORMAL_MAP
+uniform sampler2D texture_normal1;
+
+// 顶点没有切线数据，用屏幕空间导数构造切线空间
+vec3 perturbNormal(vec3 normal) {
+    vec3 dp1 = dFdx(FragPos);
+    vec3 dp2 = dFdy(FragPos);
+    vec2 duv1 = dFdx(TexCoords);
+    vec2 duv2 = dFdy(TexCoords);
+
+    vec3 dp2perp = cross(dp2, normal);
+    vec3 dp1perp = cross(normal, dp1);
+    vec3 T = dp2perp * duv1.x + dp1perp * duv2.x;
+    vec3 B = dp2perp * duv1.y + dp1perp * duv2.y;
+    float invmax = inversesqrt(max(dot(T, T), dot(B, B)));
+
+    vec3 mapped = texture(texture_normal1, TexCoords).rgb * 2.0 - 1.0;
+    return normalize(mat3(T * invmax, B * invmax, normal) * mapped);
+}
+#endif
+
+void main() {
+#ifdef HAS_DIFFUSE_MAP
+    vec3 color = texture(texture_diffuse1, TexCoords).rgb;
+#else
+    vec3 color = objectColor;
+#endif
+
+#ifdef HAS_SPECULAR_MAP
+    float specularStrength = texture(texture_specular1, TexCoords).r;      // 镜面光照的强度系数
+#else
+    float specularStrength = 0.5;
+#endif
+
+    vec3 norm = normalize(Normal);
+#ifdef HAS_NORMAL_MAP
+    norm = perturbNormal(norm);
+#endif
+
+    /* 环境光照 */
+    vec3 ambient = 0.1 * lightColor;
+
+    /* 漫反射光照 */
+    vec3 lightDir = normalize(lightPos - FragPos);
+    vec3 diffuse = max(dot(norm, lightDir), 0.0) * lightColor;
+
+    /* 镜面光照 */
+    vec3 viewDir = normalize(viewPos - FragPos);
+    vec3 reflectDir = reflect(-lightDir, norm);
+    float spec = pow(max(dot(viewDir, reflectDir), 0.0), 32);
+    vec3 specular = specularStrength * spec * lightColor;
+
+    FragColor = vec4((ambient + diffuse + specular) * color, 1.0);
+}
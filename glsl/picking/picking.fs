@@ -0,0 +1,11 @@
+#version 330 core
+
+// x: 物体 ID，y: 网格下标 + 1，0 表示没有物体
+out uvec2 FragID;
+
+uniform uint objectID;
+uniform uint meshID;
+
+void main() {
+    FragID = uvec2(objectID, meshID);
+}
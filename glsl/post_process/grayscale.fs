@@ -0,0 +1,13 @@
+#version 330 core
+
+out vec4 FragColor;
+
+in vec2 TexCoord;
+
+uniform sampler2D screenTexture;
+
+void main() {
+    vec4 color = texture(screenTexture, TexCoord);
+    float average = 0.2126 * color.r + 0.7152 * color.g + 0.0722 * color.b;  // 按人眼对各通道的敏感度加权
+    FragColor = vec4(vec3(average), 1.0);
+}
@@ -0,0 +1,11 @@
+#version 330 core
+
+out vec4 FragColor;
+
+in vec2 TexCoord;
+
+uniform sampler2D screenTexture;
+
+void main() {
+    FragColor = vec4(vec3(1.0 - texture(screenTexture, TexCoord)), 1.0);
+}
@@ -0,0 +1,24 @@
+#version 330 core
+
+out vec4 FragColor;
+
+in vec2 TexCoord;
+
+uniform sampler2D screenTexture;
+uniform float kernel[9];                                                    // 3x3 卷积核，按行排列
+uniform float offset;                                                       // 采样间隔，以纹理坐标为单位
+
+void main() {
+    vec2 offsets[9] = vec2[](
+        vec2(-offset,  offset), vec2(0.0,  offset), vec2(offset,  offset),
+        vec2(-offset,  0.0),    vec2(0.0,  0.0),    vec2(offset,  0.0),
+        vec2(-offset, -offset), vec2(0.0, -offset), vec2(offset, -offset)
+    );
+
+    vec3 color = vec3(0.0);
+    for (int i = 0; i < 9; i++) {
+        color += vec3(texture(screenTexture, TexCoord + offsets[i])) * kernel[i];
+    }
+
+    FragColor = vec4(color, 1.0);
+}
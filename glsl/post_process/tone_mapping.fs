@@ -0,0 +1,14 @@
+#version 330 core
+
+out vec4 FragColor;
+
+in vec2 TexCoord;
+
+uniform sampler2D screenTexture;
+uniform float exposure;                                                     // 曝光度
+
+void main() {
+    vec3 hdrColor = texture(screenTexture, TexCoord).rgb;
+    vec3 mapped = vec3(1.0) - exp(-hdrColor * exposure);                    // 曝光色调映射，将 HDR 颜色映射到 [0, 1]
+    FragColor = vec4(mapped, 1.0);
+}
@@ -0,0 +1,186 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr, Type};
+
+/**
+ * 为结构体实现 opengl_rs::base::uniform::Uniforms，字段按名字映射到 GLSL 结构体成员
+ *
+ * 字段属性：
+ * #[uniform(rename = "name")]  GLSL 中的成员名
+ * #[uniform(nested)]           字段本身也实现了 Uniforms，数组按 "name[i].member" 展开
+ * #[uniform(skip)]             不上传
+ *
+ * 数组与 Vec 字段整体上传到 GLSL 数组
+ */
+#[proc_macro_derive(Uniforms, attributes(uniform))]
+pub fn derive_uniforms(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/**
+ * 为结构体实现 opengl_rs::base::std140::Std140，成员按声明顺序以 std140 布局排列
+ * 支持 #[uniform(rename = "name")] 指定 GLSL 中的成员名，用于与 uniform 块比较偏移
+ */
+#[proc_macro_derive(Std140, attributes(uniform))]
+pub fn derive_std140(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand_std140(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+// 字段上 #[uniform(...)] 的配置
+#[derive(Default)]
+struct FieldAttrs {
+    rename: Option<String>,
+    nested: bool,
+    skip: bool,
+}
+
+fn named_fields<'a>(input: &'a DeriveInput, derive: &str) -> syn::Result<&'a syn::punctuated::Punctuated<syn::Field, syn::token::Comma>> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(&fields.named),
+            _ => Err(syn::Error::new_spanned(&input.ident, format!("{} can only be derived for structs with named fields", derive))),
+        },
+        _ => Err(syn::Error::new_spanned(&input.ident, format!("{} can only be derived for structs", derive))),
+    }
+}
+
+fn expand_std140(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = named_fields(input, "Std140")?;
+    let std140 = quote! { ::opengl_rs::base::std140 };
+
+    let mut align = quote! { 0 };
+    let mut end = quote! { 0 };
+    let mut writes = Vec::new();
+    let mut offsets = Vec::new();
+    for field in fields {
+        let attrs = parse_attrs(field)?;
+        if attrs.skip { continue; }
+
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let member = attrs.rename.unwrap_or_else(|| ident.to_string());
+
+        align = quote! { #std140::max(#align, <#ty as #std140::Std140>::ALIGN) };
+        end = quote! { #std140::align_up(#end, <#ty as #std140::Std140>::ALIGN) + <#ty as #std140::Std140>::SIZE };
+        writes.push(quote! {
+            offset = #std140::align_up(offset, <#ty as #std140::Std140>::ALIGN);
+            #std140::Std140::write_std140(&self.#ident, &mut out[offset..offset + <#ty as #std140::Std140>::SIZE]);
+            offset += <#ty as #std140::Std140>::SIZE;
+        });
+        offsets.push(quote! {
+            offset = #std140::align_up(offset, <#ty as #std140::Std140>::ALIGN);
+            ret.push((#member, offset));
+            offset += <#ty as #std140::Std140>::SIZE;
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #std140::Std140 for #ident #ty_generics #where_clause {
+            // 结构体按最大成员对齐，并且至少按 16 字节对齐
+            const ALIGN: usize = #std140::align_up(#align, 16);
+            const SIZE: usize = #std140::align_up(#end, Self::ALIGN);
+
+            #[allow(unused_assignments, unused_mut, unused_variables)]
+            fn write_std140(&self, out: &mut [u8]) {
+                let mut offset = 0;
+                #(#writes)*
+            }
+
+            #[allow(unused_assignments, unused_mut, unused_variables)]
+            fn member_offsets() -> ::std::vec::Vec<(&'static str, usize)> {
+                let mut offset = 0;
+                let mut ret = ::std::vec::Vec::new();
+                #(#offsets)*
+                ret
+            }
+        }
+    })
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = named_fields(input, "Uniforms")?;
+
+    let mut statements = Vec::new();
+    for field in fields {
+        let attrs = parse_attrs(field)?;
+        if attrs.skip { continue; }
+
+        let ident = field.ident.as_ref().unwrap();
+        let member = attrs.rename.unwrap_or_else(|| ident.to_string());
+        let name = quote! { &format!("{}.{}", prefix, #member) };
+
+        let is_sequence = matches!(field.ty, Type::Array(_)) || is_vec(&field.ty);
+
+        let statement = match (is_sequence, attrs.nested) {
+            (true, true) => quote! {
+                for (i, value) in self.#ident.iter().enumerate() {
+                    ::opengl_rs::base::uniform::Uniforms::set_uniforms(value, program, &format!("{}.{}[{}]", prefix, #member, i))?;
+                }
+            },
+            (false, true) => quote! {
+                ::opengl_rs::base::uniform::Uniforms::set_uniforms(&self.#ident, program, #name)?;
+            },
+            (true, false) => quote! { program.set_uniform_array(#name, &self.#ident)?; },
+            (false, false) => quote! { program.set_uniform(#name, &self.#ident)?; },
+        };
+        statements.push(statement);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::opengl_rs::base::uniform::Uniforms for #ident #ty_generics #where_clause {
+            unsafe fn set_uniforms(
+                &self,
+                program: &::opengl_rs::base::program::ShaderProgram,
+                prefix: &str,
+            ) -> ::std::result::Result<(), ::opengl_rs::base::error::ShaderError> {
+                #(#statements)*
+                Ok(())
+            }
+        }
+    })
+}
+
+fn parse_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut ret = FieldAttrs::default();
+
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("uniform")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                ret.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("nested") {
+                ret.nested = true;
+            } else if meta.path.is_ident("skip") {
+                ret.skip = true;
+            } else {
+                return Err(meta.error("expected `rename`, `nested` or `skip`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(ret)
+}
+
+fn is_vec(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.last().is_some_and(|segment| segment.ident == "Vec"),
+        _ => false,
+    }
+}
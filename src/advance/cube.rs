@@ -64,7 +64,6 @@ pub struct Cube {
     camera: Rc<RefCell<Camera>>,
 
     pos: Vec<[f32; 3]>,                 // 正方体的位置
-    win_size: (u32, u32),               // 窗口宽高
 }
 
 impl Cube {
@@ -92,6 +91,8 @@ impl Cube {
         let texture_1 = Texture::new(image.1, gl::REPEAT, gl::REPEAT, gl::LINEAR, gl::LINEAR)?;
         program.set_int("texture2", 1)?;
 
+        camera.borrow_mut().set_aspect(size.0 as f32 / size.1 as f32);
+
         let renderer = Self {
             program,
             vertex_buffer,
@@ -100,7 +101,6 @@ impl Cube {
             texture_1,
             pos,
             camera,
-            win_size: size,
         };
 
         Ok(renderer)
@@ -116,11 +116,9 @@ impl IRenderer for Cube {
         self.program.apply();
         self.vertex_array.bind();
 
-        let win_radio = (self.win_size.0 / self.win_size.1) as f32;
-
         let view = self.camera.borrow().get_view_matrix();
 
-        let projection = glm::perspective(win_radio, f32::to_radians(self.camera.borrow().get_fov()), 0.1, 100.0);
+        let projection = self.camera.borrow().get_projection_matrix();
 
         self.program.set_mat4("view", glm::value_ptr::<f32, 4, 4>(&view))?;
         self.program.set_mat4("projection", glm::value_ptr::<f32, 4, 4>(&projection))?;
@@ -22,7 +22,6 @@ pub struct Sphere{
 
     camera: Rc<RefCell<Camera>>,
 
-    win_size: (u32, u32),               // 窗口宽高
 }
 
 impl Sphere {
@@ -43,12 +42,13 @@ impl Sphere {
         let pos_attr = program.get_attr_location("aPos")?;
         set_attribute!(vertex_array, pos_attr, Vertex::0);
 
+        camera.borrow_mut().set_aspect(size.0 as f32 / size.1 as f32);
+
         let renderer = Self {
             program,
             vertex_buffer,
             vertex_array,
             camera,
-            win_size: size,
         };
 
         Ok(renderer)
@@ -103,11 +103,9 @@ impl IRenderer for Sphere {
         self.program.apply();
         self.vertex_array.bind();
 
-        let win_radio = (self.win_size.0 / self.win_size.1) as f32;
-
         let view = self.camera.borrow().get_view_matrix();
 
-        let projection = glm::perspective(win_radio, f32::to_radians(self.camera.borrow().get_fov()), 0.1, 100.0);
+        let projection = self.camera.borrow().get_projection_matrix();
 
         self.program.set_mat4("view", glm::value_ptr::<f32, 4, 4>(&view))?;
         self.program.set_mat4("projection", glm::value_ptr::<f32, 4, 4>(&projection))?;
@@ -1,4 +1,4 @@
-use gl::types::{GLuint, GLsizeiptr};
+use gl::types::{GLuint, GLsizeiptr, GLintptr};
 
 pub struct Buffer {
     pub id: GLuint,
@@ -24,6 +24,19 @@ impl Buffer {
 
     pub unsafe fn bind(&self) { gl::BindBuffer(self.target, self.id); }
 
+    // 从 offset 字节处开始覆盖数据，不改变缓冲的大小
+    pub unsafe fn set_sub_data<T>(&self, offset: usize, data: &[T]) {
+        self.bind();
+
+        let (_, data_bytes, _) = data.align_to::<u8>();
+        gl::BufferSubData(
+            self.target, 
+            offset as GLintptr, 
+            data_bytes.len() as GLsizeiptr, 
+            data_bytes.as_ptr() as *const _
+        );
+    }
+
     unsafe fn set_data<T>(&self, data: &[T], usage: GLuint) {
         self.bind();
 
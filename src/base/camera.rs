@@ -2,11 +2,49 @@
 
 use nalgebra_glm as glm;
 
+use crate::base::frustum::Frustum;
+use crate::base::std140::Std140;
+
+// 着色器中摄像机 uniform 块的名字，见 glsl/include/camera.glsl
+pub const CAMERA_BLOCK: &str = "Camera";
+
+/**
+ * 摄像机 uniform 块的数据，由引擎每帧上传一次，所有程序共享
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Std140)]
+pub struct CameraBlock {
+    pub view: glm::Mat4,
+    pub projection: glm::Mat4,
+    #[uniform(rename = "viewPos")]
+    pub view_pos: glm::Vec3,
+}
+
+impl CameraBlock {
+    pub fn from_camera(camera: &Camera) -> Self {
+        Self { view: camera.get_view_matrix(), projection: camera.get_projection_matrix(), view_pos: camera.get_pos() }
+    }
+}
+
 /**
  * 摄像机移动的方向
+ * RollLeft、RollRight 为绕视线方向的滚转
  */
 #[derive(Debug)]
-pub enum CameraMovement { Forward, Bakcward, Left, Right }
+pub enum CameraMovement { Forward, Bakcward, Left, Right, Up, Down, RollLeft, RollRight }
+
+/**
+ * 摄像机的控制方式
+ * Fly: 第一人称自由飞行，鼠标控制朝向
+ * Orbit: 围绕目标点旋转，滚轮调整与目标点的距离，可平移目标点
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CameraMode { Fly, Orbit }
+
+/**
+ * 投影方式
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection { Perspective, Orthographic }
 
 pub struct Camera {
     pos: glm::Vec3,             // 摄像机的位置
@@ -15,14 +53,31 @@ pub struct Camera {
     right: glm::Vec3,
     world_up: glm::Vec3,
 
-    yaw: f32,                   // 偏航角
-    pitch: f32,                 // 俯仰角
+    orientation: glm::Quat,     // 朝向，front、up、right 都由它计算
+    is_free_look: bool,         // 自由视角：绕自身坐标轴旋转，不限制俯仰角，可以滚转
 
     move_speed: f32,            
+    speed_multiplier: f32,      // 移动速度的倍率，例如按住加速键时大于 1
+    roll_speed: f32,            // 滚转速度，单位为 度/秒
     mouse_sensitivity: f32,
     fov: f32,                   // 视野（Field of View），定义了我们可以看到场景中多大的范围
+
+    mode: CameraMode,
+    target: glm::Vec3,          // 环绕模式下围绕的目标点
+    distance: f32,              // 环绕模式下与目标点的距离
+
+    projection: Projection,
+    aspect: f32,                // 宽高比
+    near: f32,                  // 近平面
+    far: f32,                   // 远平面
+    ortho_height: f32,          // 正交投影下视景体的高度，环绕模式下由距离与视野计算
 }
 
+// 环绕模式下与目标点的最小距离
+const MIN_ORBIT_DISTANCE: f32 = 0.1;
+// 非自由视角下俯仰角的限制
+const MAX_PITCH: f32 = 89.0;
+
 #[allow(dead_code)]
 impl Camera {
     pub fn new(pos: glm::Vec3) -> Self {
@@ -32,17 +87,63 @@ impl Camera {
             up: glm::vec3(0.0, 1.0, 0.0), 
             right: glm::vec3(0.0, 0.0, 0.0), 
             world_up: glm::vec3(0.0, 1.0, 0.0), 
-            yaw: -90.0, 
-            pitch: 0.0, 
+            orientation: glm::quat_identity(),
+            is_free_look: false,
             move_speed: 2.5, 
+            speed_multiplier: 1.0,
+            roll_speed: 90.0,
             mouse_sensitivity: 0.1, 
-            fov: 45.0 
+            fov: 45.0,
+            mode: CameraMode::Fly,
+            target: pos + glm::vec3(0.0, 0.0, -1.0),
+            distance: 1.0,
+            projection: Projection::Perspective,
+            aspect: 1.0,
+            near: 0.1,
+            far: 100.0,
+            ortho_height: 2.0,
         };
         ret.update_camera_vector();
 
         ret
     }
 
+    // 环绕模式的摄像机，从 +z 方向看向目标点
+    pub fn new_orbit(target: glm::Vec3, distance: f32) -> Self {
+        let mut ret = Self::new(target + glm::vec3(0.0, 0.0, distance));
+        ret.mode = CameraMode::Orbit;
+        ret.target = target;
+        ret.distance = distance.max(MIN_ORBIT_DISTANCE);
+        ret.update_camera_vector();
+
+        ret
+    }
+
+    pub fn get_mode(&self) -> CameraMode { self.mode }
+
+    // 切换到环绕模式时，以当前视线前方 distance 处作为目标点
+    pub fn set_mode(&mut self, mode: CameraMode) {
+        if mode == CameraMode::Orbit && self.mode != mode {
+            self.target = self.pos + self.front * self.distance;
+        }
+        self.mode = mode;
+        self.update_camera_vector();
+    }
+
+    pub fn get_target(&self) -> glm::Vec3 { self.target }
+
+    pub fn set_target(&mut self, target: glm::Vec3) {
+        self.target = target;
+        self.update_camera_vector();
+    }
+
+    pub fn get_distance(&self) -> f32 { self.distance }
+
+    pub fn set_distance(&mut self, distance: f32) {
+        self.distance = distance.max(MIN_ORBIT_DISTANCE);
+        self.update_camera_vector();
+    }
+
     pub fn get_view_matrix(&self) -> glm::Mat4{
         glm::look_at(&self.pos, &(self.pos + self.front), &self.up)
     }
@@ -51,18 +152,107 @@ impl Camera {
 
     pub fn get_front(&self) -> glm::Vec3 { self.front }
 
+    pub fn set_pos(&mut self, pos: glm::Vec3) {
+        self.pos = pos;
+        if self.mode == CameraMode::Orbit { self.target = pos + self.front * self.distance; }
+    }
+
+    /**
+     * 摄像机的朝向，将局部坐标系（-z 为前方，+y 为上方）旋转到世界坐标系
+     */
+    pub fn get_orientation(&self) -> glm::Quat { self.orientation }
+
+    // 设置朝向，环绕模式下保持位置不变，目标点移动到视线前方
+    pub fn set_orientation(&mut self, orientation: &glm::Quat) {
+        let pos = self.pos;
+        self.orientation = glm::quat_normalize(orientation);
+        self.update_camera_vector();
+
+        if self.mode == CameraMode::Orbit { self.set_pos(pos); }
+    }
+
+    pub fn is_free_look(&self) -> bool { self.is_free_look }
+
+    // 关闭自由视角时去掉滚转，恢复以世界坐标的上方向为基准
+    pub fn set_free_look(&mut self, enabled: bool) {
+        self.is_free_look = enabled;
+        if !enabled {
+            let orientation = self.level_orientation();
+            self.set_orientation(&orientation);
+        }
+    }
+
+    pub fn set_move_speed(&mut self, speed: f32) { self.move_speed = speed; }
+
+    pub fn set_speed_multiplier(&mut self, multiplier: f32) { self.speed_multiplier = multiplier; }
+
+    pub fn set_roll_speed(&mut self, speed: f32) { self.roll_speed = speed; }
+
     pub fn get_fov(&self) -> f32 { self.fov }
 
+    pub fn set_fov(&mut self, fov: f32) { self.fov = fov; }
+
+    pub fn get_projection(&self) -> Projection { self.projection }
+
+    pub fn set_projection(&mut self, projection: Projection) { self.projection = projection; }
+
+    pub fn get_aspect(&self) -> f32 { self.aspect }
+
+    // 宽高比，引擎在窗口大小改变时会自动更新
+    pub fn set_aspect(&mut self, aspect: f32) { self.aspect = aspect; }
+
+    pub fn get_clip_planes(&self) -> (f32, f32) { (self.near, self.far) }
+
+    pub fn set_clip_planes(&mut self, near: f32, far: f32) {
+        self.near = near;
+        self.far = far;
+    }
+
+    // 自由模式下正交投影视景体的高度
+    pub fn set_ortho_height(&mut self, height: f32) { self.ortho_height = height; }
+
+    pub fn get_projection_matrix(&self) -> glm::Mat4 {
+        match self.projection {
+            Projection::Perspective => glm::perspective(self.aspect, f32::to_radians(self.fov), self.near, self.far),
+            Projection::Orthographic => {
+                // 环绕模式下保持与透视投影在目标点处相同的取景范围
+                let height = match self.mode {
+                    CameraMode::Fly => self.ortho_height,
+                    CameraMode::Orbit => 2.0 * self.distance * f32::to_radians(self.fov / 2.0).tan(),
+                };
+                let (half_w, half_h) = (height * self.aspect / 2.0, height / 2.0);
+                glm::ortho(-half_w, half_w, -half_h, half_h, self.near, self.far)
+            },
+        }
+    }
+
+    pub fn get_view_projection_matrix(&self) -> glm::Mat4 {
+        self.get_projection_matrix() * self.get_view_matrix()
+    }
+
+    // 世界空间的视锥体，用于剔除
+    pub fn get_frustum(&self) -> Frustum { Frustum::from_matrix(&self.get_view_projection_matrix()) }
+
     // 处理键盘事件
     pub fn process_keyboard(&mut self, direction: CameraMovement, delta_time: f32) {
-        let velocity = self.move_speed * delta_time;
+        let velocity = self.move_speed * self.speed_multiplier * delta_time;
+        // 自由视角下沿自身的上方向升降，否则沿世界坐标的上方向
+        let up = if self.is_free_look { self.up } else { self.world_up };
 
-        match direction {
-            CameraMovement::Forward => self.pos += velocity * self.front,
-            CameraMovement::Bakcward => self.pos -= velocity * self.front,
-            CameraMovement::Left => self.pos -= velocity * self.right,
-            CameraMovement::Right => self.pos += velocity * self.right,
-        }
+        let offset = match direction {
+            CameraMovement::Forward => velocity * self.front,
+            CameraMovement::Bakcward => -velocity * self.front,
+            CameraMovement::Left => -velocity * self.right,
+            CameraMovement::Right => velocity * self.right,
+            CameraMovement::Up => velocity * up,
+            CameraMovement::Down => -velocity * up,
+            CameraMovement::RollLeft => return self.roll(self.roll_speed * delta_time),
+            CameraMovement::RollRight => return self.roll(-self.roll_speed * delta_time),
+        };
+
+        // 环绕模式下连同目标点一起移动
+        self.pos += offset;
+        self.target += offset;
     }
 
     // 处理鼠标移动事件
@@ -70,31 +260,80 @@ impl Camera {
         x_offset *= self.mouse_sensitivity;
         y_offset *= self.mouse_sensitivity;
 
-        self.yaw += x_offset;
-        self.pitch += y_offset;
+        let yaw = f32::to_radians(-x_offset);
+        if self.is_free_look {
+            // 绕自身的上方向偏航、绕自身的右方向俯仰，没有万向节锁
+            self.orientation = self.orientation 
+                * glm::quat_angle_axis(yaw, &glm::vec3(0.0, 1.0, 0.0)) 
+                * glm::quat_angle_axis(f32::to_radians(y_offset), &glm::vec3(1.0, 0.0, 0.0));
+        } else {
+            // 绕世界坐标的上方向偏航，俯仰角限制在 ±89° 以内
+            let pitch = f32::to_degrees(glm::dot(&self.front, &self.world_up).clamp(-1.0, 1.0).asin());
+            let y_offset = (pitch + y_offset).clamp(-MAX_PITCH, MAX_PITCH) - pitch;
+
+            self.orientation = glm::quat_angle_axis(yaw, &self.world_up) 
+                * self.orientation 
+                * glm::quat_angle_axis(f32::to_radians(y_offset), &glm::vec3(1.0, 0.0, 0.0));
+        }
+        self.orientation = glm::quat_normalize(&self.orientation);
 
-        // 限制视角
-        if self.pitch > 89.0 { self.pitch = 89.0; }
-        if self.pitch < -89.0 { self.pitch = -89.0; }
+        self.update_camera_vector();
+    }
 
+    // 绕视线方向滚转，angle 为正时向左滚转，单位为度
+    pub fn roll(&mut self, angle: f32) {
+        self.orientation = glm::quat_normalize(&(self.orientation * glm::quat_angle_axis(f32::to_radians(angle), &glm::vec3(0.0, 0.0, 1.0))));
         self.update_camera_vector();
     }
 
-    // 处理鼠标滚动事件
+    // 平移：环绕模式下在视平面内移动目标点，移动速度与距离成正比
+    pub fn process_mouse_pan(&mut self, x_offset: f32, y_offset: f32) {
+        let scale = self.distance * self.mouse_sensitivity * 0.01;
+        let offset = -x_offset * scale * self.right - y_offset * scale * self.up;
+
+        self.pos += offset;
+        self.target += offset;
+    }
+
+    // 处理鼠标滚动事件：自由模式下缩放视野，环绕模式下调整与目标点的距离
     pub fn process_mouse_scroll(&mut self, y_offset: f32) {
-        self.fov -= y_offset;
+        match self.mode {
+            CameraMode::Fly => self.fov = (self.fov - y_offset).clamp(1.0, 45.0),
+            CameraMode::Orbit => {
+                self.distance = (self.distance * (1.0 - y_offset * 0.1)).max(MIN_ORBIT_DISTANCE);
+                self.update_camera_vector();
+            },
+        }
+    }
 
-        if self.fov < 1.0 { self.fov = 1.0; }
-        if self.fov > 45.0 { self.fov = 45.0; }
+    // 保持视线方向、去掉滚转后的朝向，视线接近竖直时限制俯仰角
+    fn level_orientation(&self) -> glm::Quat {
+        let max_y = f32::to_radians(MAX_PITCH).sin();
+        let mut front = self.front;
+        let horizontal = glm::vec2(front.x, front.z);
+        let horizontal = if glm::length(&horizontal) > 1e-4 { glm::normalize(&horizontal) } else { glm::vec2(0.0, -1.0) };
+        if front.y.abs() > max_y {
+            let scale = (1.0 - max_y * max_y).sqrt();
+            front = glm::vec3(horizontal.x * scale, max_y * front.y.signum(), horizontal.y * scale);
+        }
+
+        let right = glm::normalize(&glm::cross(&front, &self.world_up));
+        let up = glm::normalize(&glm::cross(&right, &front));
+        let rotation = glm::mat3(
+            right.x, up.x, -front.x,
+            right.y, up.y, -front.y,
+            right.z, up.z, -front.z,
+        );
+        glm::quat_normalize(&glm::mat3_to_quat(&rotation))
     }
 
     fn update_camera_vector(&mut self) {
-        let x = f32::to_radians(self.yaw).cos() * f32::to_radians(self.pitch).cos();
-        let y = f32::to_radians(self.pitch).sin();
-        let z = f32::to_radians(self.yaw).sin() * f32::to_radians(self.pitch).cos();
-        self.front = glm::normalize(&glm::Vec3::new(x, y, z));
+        self.front = glm::normalize(&glm::quat_rotate_vec3(&self.orientation, &glm::vec3(0.0, 0.0, -1.0)));
+        self.up = glm::normalize(&glm::quat_rotate_vec3(&self.orientation, &glm::vec3(0.0, 1.0, 0.0)));
+        self.right = glm::normalize(&glm::quat_rotate_vec3(&self.orientation, &glm::vec3(1.0, 0.0, 0.0)));
 
-        self.right = glm::normalize(&glm::cross(&self.front, &self.world_up));
-        self.up = glm::normalize(&glm::cross(&self.right, &self.front));
+        if self.mode == CameraMode::Orbit {
+            self.pos = self.target - self.front * self.distance;
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,260 @@
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+
+use nalgebra_glm as glm;
+use serde::{Deserialize, Serialize};
+
+use crate::base::{camera::Camera, error::GLError};
+
+/**
+ * 关键帧：某一时刻摄像机的位置、朝向与视野
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Keyframe {
+    pub time: f32,                          // 单位为秒
+    pub position: [f32; 3],
+    pub orientation: [f32; 4],              // 四元数 (x, y, z, w)
+    pub fov: f32,
+}
+
+impl Keyframe {
+    pub fn new(time: f32, position: glm::Vec3, orientation: glm::Quat, fov: f32) -> Self {
+        let q = orientation.coords;
+        Self { time, position: [position.x, position.y, position.z], orientation: [q.x, q.y, q.z, q.w], fov }
+    }
+
+    // 记录摄像机当前的状态
+    pub fn from_camera(camera: &Camera, time: f32) -> Self {
+        Self::new(time, camera.get_pos(), camera.get_orientation(), camera.get_fov())
+    }
+
+    pub fn position(&self) -> glm::Vec3 { glm::Vec3::from(self.position) }
+
+    pub fn orientation(&self) -> glm::Quat {
+        let [x, y, z, w] = self.orientation;
+        glm::quat(x, y, z, w)
+    }
+
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.set_pos(self.position());
+        camera.set_orientation(&self.orientation());
+        camera.set_fov(self.fov);
+    }
+}
+
+/**
+ * 摄像机路径：位置使用 Catmull-Rom 样条插值，朝向使用球面线性插值
+ * 循环路径的最后一个关键帧应与第一个相同，经过首尾时取环绕的相邻关键帧作为切线，运动方向保持连续
+ */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CameraPath {
+    keyframes: Vec<Keyframe>,
+
+    #[serde(default)]
+    pub looped: bool,                       // 播放到结尾后是否从头开始
+}
+
+impl CameraPath {
+    pub fn new(mut keyframes: Vec<Keyframe>) -> Self {
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self { keyframes, looped: false }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GLError> {
+        let source = fs::read_to_string(path)?;
+        let path: Self = serde_json::from_str(&source)?;
+        Ok(Self { looped: path.looped, ..Self::new(path.keyframes) })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), GLError> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] { &self.keyframes }
+
+    // 按时间顺序插入关键帧
+    pub fn push(&mut self, keyframe: Keyframe) {
+        let index = self.keyframes.partition_point(|k| k.time <= keyframe.time);
+        self.keyframes.insert(index, keyframe);
+    }
+
+    // 路径的总时长
+    pub fn duration(&self) -> f32 { self.keyframes.last().map_or(0.0, |k| k.time) }
+
+    /**
+     * 计算 time 时刻的插值结果
+     * 超出路径范围时，循环路径取模，否则停留在首尾关键帧
+     */
+    pub fn sample(&self, time: f32) -> Option<Keyframe> {
+        let (first, last) = (self.keyframes.first()?, self.keyframes.last()?);
+
+        let time = if self.looped && last.time > first.time {
+            first.time + (time - first.time).rem_euclid(last.time - first.time)
+        } else {
+            time.clamp(first.time, last.time)
+        };
+
+        // 找到 time 所在的区间 [k1, k2]
+        let i = self.keyframes.partition_point(|k| k.time <= time).clamp(1, self.keyframes.len()) - 1;
+        let k1 = &self.keyframes[i];
+        let k2 = self.keyframes.get(i + 1).unwrap_or(k1);
+        let (k0, k3) = match self.wrapped_neighbours(i) {
+            Some(neighbours) => neighbours,
+            None => (if i > 0 { &self.keyframes[i - 1] } else { k1 }, self.keyframes.get(i + 2).unwrap_or(k2)),
+        };
+
+        let span = k2.time - k1.time;
+        let t = if span > 0.0 { (time - k1.time) / span } else { 0.0 };
+
+        let position = catmull_rom(&k0.position(), &k1.position(), &k2.position(), &k3.position(), t);
+        let orientation = slerp(&k1.orientation(), &k2.orientation(), t);
+        let fov = k1.fov + (k2.fov - k1.fov) * t;
+
+        Some(Keyframe::new(time, position, orientation, fov))
+    }
+
+    // 循环路径中区间 [i, i + 1] 前后的关键帧，最后一个关键帧与第一个重合，所以周期为 len - 1
+    fn wrapped_neighbours(&self, i: usize) -> Option<(&Keyframe, &Keyframe)> {
+        let period = self.keyframes.len().checked_sub(1).filter(|&n| self.looped && n >= 2)?;
+        let k0 = &self.keyframes[(i + period - 1) % period];
+        let k3 = &self.keyframes[(i + 2) % period];
+
+        Some((k0, k3))
+    }
+
+    pub fn apply(&self, camera: &mut Camera, time: f32) {
+        if let Some(keyframe) = self.sample(time) { keyframe.apply(camera); }
+    }
+}
+
+// 均匀 Catmull-Rom 样条，经过 p1、p2 两点
+fn catmull_rom(p0: &glm::Vec3, p1: &glm::Vec3, p2: &glm::Vec3, p3: &glm::Vec3, t: f32) -> glm::Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    (p1 * 2.0 
+        + (p2 - p0) * t 
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2 
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3) * 0.5
+}
+
+// 沿最短路径做球面线性插值
+fn slerp(q1: &glm::Quat, q2: &glm::Quat, t: f32) -> glm::Quat {
+    let q2 = if glm::quat_dot(q1, q2) < 0.0 { -q2 } else { *q2 };
+    glm::quat_slerp(q1, &q2, t)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-4;
+
+    fn keyframe(time: f32, position: glm::Vec3) -> Keyframe {
+        Keyframe::new(time, position, glm::quat_identity(), 45.0)
+    }
+
+    fn assert_vec_eq(a: &glm::Vec3, b: &glm::Vec3) {
+        assert!(glm::distance(a, b) < EPSILON, "{:?} != {:?}", a, b);
+    }
+
+    fn velocity(path: &CameraPath, time: f32) -> glm::Vec3 {
+        let h = 1e-3;
+        (path.sample(time + h).unwrap().position() - path.sample(time - h).unwrap().position()) / (2.0 * h)
+    }
+
+    #[test]
+    fn endpoints_return_first_and_last_keyframe() {
+        let path = CameraPath::new(vec![
+            keyframe(0.0, glm::vec3(0.0, 0.0, 0.0)),
+            keyframe(0.5, glm::vec3(1.0, 2.0, 0.0)),
+            keyframe(1.0, glm::vec3(3.0, 0.0, 1.0)),
+        ]);
+
+        assert_vec_eq(&path.sample(0.0).unwrap().position(), &glm::vec3(0.0, 0.0, 0.0));
+        assert_vec_eq(&path.sample(1.0).unwrap().position(), &glm::vec3(3.0, 0.0, 1.0));
+        // 经过中间的关键帧
+        assert_vec_eq(&path.sample(0.5).unwrap().position(), &glm::vec3(1.0, 2.0, 0.0));
+        // 超出范围时停留在首尾
+        assert_vec_eq(&path.sample(-1.0).unwrap().position(), &glm::vec3(0.0, 0.0, 0.0));
+        assert_vec_eq(&path.sample(2.0).unwrap().position(), &glm::vec3(3.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn catmull_rom_matches_known_value() {
+        let path = CameraPath::new(vec![
+            keyframe(0.0, glm::vec3(0.0, 0.0, 0.0)),
+            keyframe(1.0, glm::vec3(1.0, 1.0, 0.0)),
+            keyframe(2.0, glm::vec3(2.0, 0.0, 0.0)),
+            keyframe(3.0, glm::vec3(3.0, 1.0, 0.0)),
+        ]);
+
+        // 区间 [1, 2] 的中点：(-p0 + 9 p1 + 9 p2 - p3) / 16
+        assert_vec_eq(&path.sample(1.5).unwrap().position(), &glm::vec3(1.5, 0.5, 0.0));
+        assert!((path.sample(1.5).unwrap().fov - 45.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn empty_path_has_no_sample() {
+        assert!(CameraPath::default().sample(0.0).is_none());
+    }
+
+    #[test]
+    fn slerp_takes_shortest_path_across_sign_flip() {
+        let axis = glm::vec3(0.0, 1.0, 0.0);
+        let start = glm::quat_identity();
+        // 与旋转 20° 表示同一朝向，但符号相反
+        let end = -glm::quat_angle_axis(20_f32.to_radians(), &axis);
+
+        let path = CameraPath::new(vec![
+            Keyframe::new(0.0, glm::Vec3::zeros(), start, 45.0),
+            Keyframe::new(1.0, glm::Vec3::zeros(), end, 45.0),
+        ]);
+        let middle = path.sample(0.5).unwrap().orientation();
+
+        // 中点只旋转 10°，而不是绕远路的 190°
+        let angle = 2.0 * glm::quat_dot(&middle, &start).abs().min(1.0).acos();
+        assert!((angle.to_degrees() - 10.0).abs() < 1e-2, "angle = {}", angle.to_degrees());
+    }
+
+    #[test]
+    fn looped_path_wraps_time() {
+        let mut path = CameraPath::new(vec![
+            keyframe(0.0, glm::vec3(0.0, 0.0, 0.0)),
+            keyframe(1.0, glm::vec3(1.0, 0.0, 0.0)),
+            keyframe(2.0, glm::vec3(0.0, 0.0, 0.0)),
+        ]);
+        path.looped = true;
+
+        assert_vec_eq(&path.sample(2.0).unwrap().position(), &glm::vec3(0.0, 0.0, 0.0));
+        assert_vec_eq(&path.sample(3.0).unwrap().position(), &glm::vec3(1.0, 0.0, 0.0));
+        assert_vec_eq(&path.sample(2.25).unwrap().position(), &path.sample(0.25).unwrap().position());
+    }
+
+    #[test]
+    fn looped_path_is_smooth_at_seam() {
+        // 正方形的四个角，最后一个关键帧回到起点
+        let mut path = CameraPath::new(vec![
+            keyframe(0.0, glm::vec3(0.0, 0.0, 0.0)),
+            keyframe(1.0, glm::vec3(1.0, 0.0, 0.0)),
+            keyframe(2.0, glm::vec3(1.0, 0.0, 1.0)),
+            keyframe(3.0, glm::vec3(0.0, 0.0, 1.0)),
+            keyframe(4.0, glm::vec3(0.0, 0.0, 0.0)),
+        ]);
+        path.looped = true;
+
+        // 首尾处的切线为 (p1 - p3) / 2
+        let expected = glm::vec3(0.5, 0.0, -0.5);
+        let h = 1e-3;
+        let before = (path.sample(4.0 - h).unwrap().position() - path.sample(4.0 - 2.0 * h).unwrap().position()) / h;
+        let after = (path.sample(h).unwrap().position() - path.sample(0.0).unwrap().position()) / h;
+
+        assert!(glm::distance(&before, &expected) < 1e-2, "{:?}", before);
+        assert!(glm::distance(&after, &expected) < 1e-2, "{:?}", after);
+        assert!(glm::distance(&velocity(&path, 2.0), &glm::vec3(-0.5, 0.0, 0.5)) < 1e-2);
+    }
+}
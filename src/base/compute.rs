@@ -0,0 +1,84 @@
+#![allow(dead_code)]
+
+use gl::types::{GLbitfield, GLint};
+
+use crate::base::error::ShaderError;
+use crate::base::preprocessor::Preprocessor;
+use crate::base::program::ShaderProgram;
+use crate::base::shader::{ShaderCode, ShaderStage};
+
+/**
+ * 计算着色器程序，需要 OpenGL 4.3
+ */
+pub struct ComputeProgram {
+    program: ShaderProgram,
+    work_group_size: [u32; 3],                  // 着色器中 layout (local_size_x, ...) 声明的工作组大小
+}
+
+impl ComputeProgram {
+    pub unsafe fn new(file_cs: &str) -> Result<Self, ShaderError> {
+        Self::with_preprocessor(file_cs, Preprocessor::default())
+    }
+
+    pub unsafe fn with_preprocessor(file_cs: &str, preprocessor: Preprocessor) -> Result<Self, ShaderError> {
+        let program = ShaderProgram::from_stages(vec![(ShaderCode::File(file_cs.into()), ShaderStage::Compute)], preprocessor)?;
+        Ok(Self::from_program(program))
+    }
+
+    // 由内存中的源码创建
+    pub unsafe fn from_str(code: &str) -> Result<Self, ShaderError> {
+        let program = ShaderProgram::from_stages(vec![(ShaderCode::Source { name: "<compute>".into(), code: code.into() }, ShaderStage::Compute)], Preprocessor::default())?;
+        Ok(Self::from_program(program))
+    }
+
+    unsafe fn from_program(program: ShaderProgram) -> Self {
+        let work_group_size = Self::query_work_group_size(&program);
+        Self { program, work_group_size }
+    }
+
+    // 用于设置 uniform 变量
+    pub fn program(&self) -> &ShaderProgram { &self.program }
+
+    pub fn work_group_size(&self) -> [u32; 3] { self.work_group_size }
+
+    // 按工作组数量分派
+    pub unsafe fn dispatch(&self, x: u32, y: u32, z: u32) {
+        self.program.apply();
+        gl::DispatchCompute(x, y, z);
+    }
+
+    // 按调用总数分派，工作组数量向上取整，着色器中需要自行判断越界
+    pub unsafe fn dispatch_invocations(&self, x: u32, y: u32, z: u32) {
+        let [sx, sy, sz] = self.work_group_size;
+        self.dispatch(x.div_ceil(sx), y.div_ceil(sy), z.div_ceil(sz));
+    }
+
+    /**
+     * 内存屏障，保证之前的写入对之后的读取可见
+     * barriers: 如 gl::SHADER_STORAGE_BARRIER_BIT、gl::SHADER_IMAGE_ACCESS_BARRIER_BIT
+     */
+    pub unsafe fn memory_barrier(barriers: GLbitfield) { gl::MemoryBarrier(barriers); }
+
+    // 计算着色器写入的数据随后作为顶点属性或索引读取
+    pub unsafe fn barrier_for_vertex_data() {
+        Self::memory_barrier(gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT | gl::ELEMENT_ARRAY_BARRIER_BIT);
+    }
+
+    // 计算着色器写入的图像随后被采样
+    pub unsafe fn barrier_for_textures() {
+        Self::memory_barrier(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT | gl::TEXTURE_FETCH_BARRIER_BIT);
+    }
+
+    pub unsafe fn reload_if_changed(&mut self) -> bool {
+        let reloaded = self.program.reload_if_changed();
+        if reloaded { self.work_group_size = Self::query_work_group_size(&self.program); }
+        reloaded
+    }
+
+    unsafe fn query_work_group_size(program: &ShaderProgram) -> [u32; 3] {
+        let mut size: [GLint; 3] = [1; 3];
+        gl::GetProgramiv(program.id, gl::COMPUTE_WORK_GROUP_SIZE, size.as_mut_ptr());
+
+        size.map(|s| s.max(1) as u32)
+    }
+}
@@ -1,6 +1,27 @@
-use glfw::{WindowHint, OpenGlProfileHint, WindowMode, Context, CursorMode, WindowEvent, Key, Action, GlfwReceiver, };
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
-use crate::{IRenderer, base::{error::GLError, camera::CameraMovement}};
+use glfw::{WindowHint, OpenGlProfileHint, WindowMode, Context, CursorMode, WindowEvent, GlfwReceiver, ContextCreationApi, SwapInterval, MouseButton, };
+use image::RgbaImage;
+use nalgebra_glm as glm;
+
+use crate::{IRenderer, base::{error::GLError, camera::{CameraBlock, CameraMode, CameraMovement, CAMERA_BLOCK}, camera_path::CameraPath, input::{self, InputState}, framebuffer::{Framebuffer, ColorFormat, DepthStencil}, post_process::PostProcess, stats::{FrameStats, GpuTimer}, uniform_buffer::UniformBuffer, utility}};
+
+// 默认创建的 OpenGL 上下文版本
+const DEFAULT_CONTEXT_VERSION: (u32, u32) = (3, 3);
+// update 的默认时间步长
+const DEFAULT_FIXED_TIMESTEP: f32 = 1.0 / 60.0;
+// 单帧最多计入的时间，避免卡顿后 update 追赶不上
+const MAX_FRAME_TIME: f32 = 0.25;
+// 单帧最多调用 update 的次数，超出的时间直接丢弃，避免 update 比帧更慢时越积越多
+const MAX_UPDATE_STEPS: u32 = 8;
+// 按住加速、减速键时摄像机移动速度的倍率
+const FAST_SPEED_MULTIPLIER: f32 = 4.0;
+const SLOW_SPEED_MULTIPLIER: f32 = 0.25;
+// 在标题栏显示统计数据时的刷新间隔
+const TITLE_UPDATE_INTERVAL: f32 = 0.5;
+// 检查着色器源文件修改时间的间隔
+const SHADER_POLL_INTERVAL: f32 = 0.5;
 
 pub struct Engine<T: IRenderer> {
     renderer: T,
@@ -10,48 +31,387 @@ pub struct Engine<T: IRenderer> {
 
     delta_time: f32,                                            // 上一帧跟当前帧的时间间隔
     last_frame: f32,                                            // 记录上一帧的时间
+    fixed_timestep: f32,                                        // update 的固定时间步长
+    accumulator: f32,                                           // 尚未被 update 消耗的时间
+    frame_rate_limit: Option<f32>,                              // 帧率上限
     is_first_capture: bool,                                     // 鼠标是否第一次被捕获
     cursor_pos: (f32, f32),                                     // 记录鼠标的位置
+    size: (u32, u32),                                           // 帧缓冲宽高
+    offscreen: Option<Framebuffer>,                             // 无窗口模式下的渲染目标
+
+    screenshot_dir: PathBuf,                                    // 按键截图的保存目录
+    screenshot_request: Option<PathBuf>,                        // 等待在本帧绘制后保存的截图
+
+    post_process: Option<PostProcess>,                          // 后处理链
+    is_wireframe: bool,                                         // 是否以线框模式绘制
+
+    input: InputState,                                          // 当前帧的输入状态
+    camera_path: Option<CameraPath>,                            // 正在播放的摄像机路径，播放时忽略鼠标与键盘对摄像机的控制
+    camera_path_time: f32,                                      // 摄像机路径的播放进度
+
+    win_title: String,
+    stats: FrameStats,                                          // 帧耗时统计
+    gpu_timer: GpuTimer,
+    is_stats_in_title: bool,                                    // 是否在标题栏显示帧耗时统计
+    last_title_update: f32,                                     // 上一次刷新标题栏的时间
+
+    camera_buffer: Option<UniformBuffer<CameraBlock>>,          // 摄像机 uniform 块，每帧绘制前更新一次
+
+    is_shader_hot_reload: bool,                                 // 是否在着色器源文件修改后自动重新加载
+    last_shader_poll: f32,                                      // 上一次检查着色器源文件的时间
 }
 
 impl<T: IRenderer> Engine<T> {
     pub fn new<F: Fn() -> Result<T, GLError>>(win_title: &str, size: (u32, u32), act: F) -> Result<Self, GLError> {
+        Self::with_context_version(win_title, size, DEFAULT_CONTEXT_VERSION, act)
+    }
+
+    /**
+     * 指定 OpenGL 版本创建上下文，曲面细分着色器需要 4.0，计算着色器需要 4.3
+     */
+    pub fn with_context_version<F: Fn() -> Result<T, GLError>>(win_title: &str, size: (u32, u32), version: (u32, u32), act: F) -> Result<Self, GLError> {
+        let mut glfw = glfw::init_no_callbacks()?;
+        glfw.window_hint(WindowHint::ContextVersion(version.0, version.1));
+        let mut engine = Self::create(glfw, win_title, size, act)?;
+
+        // 高分屏下帧缓冲的大小与窗口大小不同
+        let framebuffer_size = engine.window.get_framebuffer_size();
+        engine.handle_resize(framebuffer_size)?;
+
+        Ok(engine)
+    }
+
+    /**
+     * 无窗口模式：创建一个不可见的窗口，渲染结果写入离屏帧缓冲，可通过 render_frames 读回
+     * context_api: 上下文的创建方式（native、EGL 或 OSMesa），glfw 初始化仍然需要 X11 或 Wayland 显示，CI 上需要 Xvfb 等虚拟显示
+     * 每帧开始绘制时绑定离屏帧缓冲，渲染器在一帧中切换到其他帧缓冲后需要自己切换回来
+     * 无法创建上下文时返回 GLError::WindowCreationError
+     */
+    pub fn new_headless<F: Fn() -> Result<T, GLError>>(size: (u32, u32), context_api: ContextCreationApi, act: F) -> Result<Self, GLError> {
         let mut glfw = glfw::init_no_callbacks()?;
-        glfw.window_hint(WindowHint::ContextVersion(3, 3));
+        glfw.window_hint(WindowHint::ContextVersion(DEFAULT_CONTEXT_VERSION.0, DEFAULT_CONTEXT_VERSION.1));
+        glfw.window_hint(WindowHint::Visible(false));
+        glfw.window_hint(WindowHint::ContextCreationApi(context_api));
+
+        let mut engine = Self::create(glfw, "", size, act)?;
+        engine.offscreen = Some(unsafe { Framebuffer::new(size, &[ColorFormat::RGBA8], DepthStencil::DepthStencil)? });
+        engine.renderer.on_resize(size);
+        if let Some(camera) = engine.renderer.getCamera() { camera.borrow_mut().set_aspect(size.0 as f32 / size.1 as f32); }
+
+        Ok(engine)
+    }
+
+    fn create<F: Fn() -> Result<T, GLError>>(mut glfw: glfw::Glfw, win_title: &str, size: (u32, u32), act: F) -> Result<Self, GLError> {
         glfw.window_hint(WindowHint::OpenGlProfile(OpenGlProfileHint::Core));
 
         // 创建窗口
-        let (mut window, event_receiver) = glfw.create_window(size.0, size.1, win_title, WindowMode::Windowed).expect("Failed to create GLFW window.");
+        let (mut window, event_receiver) = glfw.create_window(size.0, size.1, win_title, WindowMode::Windowed).ok_or(GLError::WindowCreationError)?;
         window.make_current();
 
         // 找到opengl函数地址
         gl::load_with(|ptr| window.get_proc_address(ptr) as *const _);
 
+        // 摄像机 uniform 块需要在 act 创建程序之前注册，程序链接时才会检查块的布局
+        let camera_buffer = match T::USES_CAMERA_BLOCK {
+            true => Some(unsafe { UniformBuffer::new(CAMERA_BLOCK, &CameraBlock { view: glm::identity(), projection: glm::identity(), view_pos: glm::Vec3::zeros() })? }),
+            false => None,
+        };
+
         let renderer = act()?;
 
         // 注册事件
-        if renderer.getCamera().is_some() { 
-            window.set_cursor_pos_polling(true); 
-            // 捕获鼠标
+        if renderer.getCamera().is_some_and(|camera| camera.borrow().get_mode() == CameraMode::Fly) { 
+            // 捕获鼠标，环绕模式下需要显示鼠标以便拖拽
             window.set_cursor_mode(CursorMode::Disabled);
         }
+        window.set_cursor_pos_polling(true); 
         window.set_framebuffer_size_polling(true);
+        window.set_key_polling(true);
+        window.set_mouse_button_polling(true);
+        window.set_scroll_polling(true);
+        window.set_char_polling(true);
         
         let cursor_pos = (size.0 as f32 / 2.0, size.1 as f32 / 2.0);
-        Ok( Self{ renderer, glfw, window, event_receiver, delta_time: 0.0, last_frame: 0.0, is_first_capture: true, cursor_pos } )
+        Ok( Self{ renderer, glfw, window, event_receiver, delta_time: 0.0, last_frame: 0.0,
+            fixed_timestep: DEFAULT_FIXED_TIMESTEP, accumulator: 0.0, frame_rate_limit: None, is_first_capture: true, cursor_pos, size, offscreen: None,
+            screenshot_dir: PathBuf::from("screenshots"), screenshot_request: None,
+            post_process: None, is_wireframe: false, input: InputState::default(), camera_path: None, camera_path_time: 0.0,
+            win_title: win_title.into(), stats: FrameStats::default(), gpu_timer: unsafe { GpuTimer::new() }, is_stats_in_title: false, last_title_update: 0.0,
+            camera_buffer, is_shader_hot_reload: cfg!(debug_assertions), last_shader_poll: 0.0 } )
+    }
+
+    /**
+     * 以固定的帧间隔渲染 frames 帧，并读回最后一帧的像素
+     * 每帧正好经过一个固定步长，结果与机器的帧率无关
+     * 无窗口模式下读取离屏帧缓冲，否则读取默认帧缓冲
+     */
+    pub fn render_frames(&mut self, frames: u32) -> Result<RgbaImage, GLError> {
+        unsafe {
+            match &self.offscreen {
+                Some(target) => target.bind(),
+                None => gl::Viewport(0, 0, self.size.0 as i32, self.size.1 as i32),
+            }
+
+            self.renderer.pre_draw()?;
+
+            let mut img = RgbaImage::new(self.size.0, self.size.1);
+            for frame in 0..frames {
+                let alpha = self.step(self.fixed_timestep);
+                self.draw_frame(alpha)?;
+
+                // 在交换缓冲前读取，此时后缓冲中是完整的当前帧
+                if frame + 1 == frames { img = self.capture(); }
+                if self.offscreen.is_none() { self.window.swap_buffers(); }
+            }
+
+            Ok(img)
+        }
+    }
+
+    /**
+     * 设置后处理链，之后每一帧都会先绘制到离屏帧缓冲，再依次经过启用的效果
+     * 运行时可按数字键 1-9 切换对应效果的启用状态
+     */
+    pub fn set_post_process(&mut self, mut post_process: PostProcess) -> Result<(), GLError> { 
+        unsafe { post_process.resize(self.size)?; }
+        self.post_process = Some(post_process);
+        Ok(())
+    }
+
+    pub fn post_process_mut(&mut self) -> Option<&mut PostProcess> { self.post_process.as_mut() }
+
+    pub fn input(&self) -> &InputState { &self.input }
+
+    pub fn input_mut(&mut self) -> &mut InputState { &mut self.input }
+
+    // 从配置文件加载按键绑定，见 ActionMap::load
+    pub fn load_bindings<P: AsRef<Path>>(&mut self, path: P) -> Result<(), GLError> {
+        self.input.actions_mut().load(path)?;
+        Ok(())
+    }
+
+    // 当前帧缓冲的宽高
+    pub fn framebuffer_size(&self) -> (u32, u32) { self.size }
+
+    fn handle_resize(&mut self, size: (i32, i32)) -> Result<(), GLError> {
+        // 窗口最小化时宽高为 0，不需要处理
+        if size.0 <= 0 || size.1 <= 0 { return Ok(()); }
+
+        self.size = (size.0 as u32, size.1 as u32);
+        unsafe {
+            gl::Viewport(0, 0, size.0, size.1);
+            if let Some(post_process) = &mut self.post_process { post_process.resize(self.size)?; }
+        }
+        if let Some(camera) = self.renderer.getCamera() { camera.borrow_mut().set_aspect(size.0 as f32 / size.1 as f32); }
+        self.renderer.on_resize(self.size);
+
+        Ok(())
+    }
+
+    // 设置 update 的固定时间步长，单位为秒，必须是正的有限值
+    pub fn set_fixed_timestep(&mut self, dt: f32) {
+        assert!(dt > 0.0 && dt.is_finite(), "fixed timestep must be positive and finite, got {}", dt);
+        self.fixed_timestep = dt;
+    }
+
+    // 开启或关闭垂直同步
+    pub fn set_vsync(&mut self, enabled: bool) {
+        self.glfw.set_swap_interval(if enabled { SwapInterval::Sync(1) } else { SwapInterval::None });
+    }
+
+    // 设置帧率上限，None 表示不限制
+    pub fn set_frame_rate_limit(&mut self, fps: Option<f32>) { self.frame_rate_limit = fps; }
+
+    // 从头播放摄像机路径，render_frames 中每帧推进一个固定步长，结果可以复现
+    pub fn play_camera_path(&mut self, path: CameraPath) {
+        self.camera_path = Some(path);
+        self.camera_path_time = 0.0;
+        self.update_camera_path(0.0);
+    }
+
+    pub fn stop_camera_path(&mut self) { self.camera_path = None; }
+
+    pub fn is_playing_camera_path(&self) -> bool { self.camera_path.is_some() }
+
+    fn update_camera_path(&mut self, frame_time: f32) {
+        let Some(path) = &self.camera_path else { return };
+        self.camera_path_time += frame_time;
+
+        if let Some(camera) = self.renderer.getCamera() { path.apply(&mut camera.borrow_mut(), self.camera_path_time); }
+
+        // 非循环的路径播放完毕
+        if !path.looped && self.camera_path_time >= path.duration() { self.camera_path = None; }
+    }
+
+    /**
+     * 推进 frame_time 秒，按固定步长调用 update
+     * 返回剩余时间占步长的比例，作为绘制时的插值系数
+     */
+    fn step(&mut self, frame_time: f32) -> f32 {
+        let frame_time = frame_time.min(MAX_FRAME_TIME);
+        self.delta_time = frame_time;
+
+        self.accumulator += frame_time;
+        let mut steps = 0;
+        while self.accumulator >= self.fixed_timestep {
+            if steps == MAX_UPDATE_STEPS {
+                self.accumulator %= self.fixed_timestep;
+                break;
+            }
+            self.renderer.update(self.fixed_timestep);
+            self.accumulator -= self.fixed_timestep;
+            steps += 1;
+        }
+        self.update_camera_path(frame_time);
+
+        self.accumulator / self.fixed_timestep
+    }
+
+    // 最近若干帧的耗时统计
+    pub fn frame_stats(&self) -> &FrameStats { &self.stats }
+
+    // 在标题栏显示 FPS 与耗时统计
+    pub fn set_stats_in_title(&mut self, enabled: bool) {
+        self.is_stats_in_title = enabled;
+        if !enabled { self.window.set_title(&self.win_title); }
+    }
+
+    fn update_title(&mut self, now: f32) {
+        if !self.is_stats_in_title || now - self.last_title_update < TITLE_UPDATE_INTERVAL { return; }
+        self.last_title_update = now;
+
+        let stats = &self.stats;
+        let title = format!(
+            "{} | {:.1} FPS | frame {:.2} ms (p99 {:.2}) | cpu {:.2} ms | gpu {:.2} ms (p99 {:.2})",
+            self.win_title, 
+            stats.fps(), 
+            stats.frame_time(), 
+            stats.frame_time_percentile(99.0), 
+            stats.cpu_time(), 
+            stats.gpu_time(), 
+            stats.gpu_time_percentile(99.0)
+        );
+        self.window.set_title(&title);
+    }
+
+    // 着色器热重载，debug 构建下默认开启
+    pub fn set_shader_hot_reload(&mut self, enabled: bool) { self.is_shader_hot_reload = enabled; }
+
+    fn poll_shaders(&mut self, now: f32) {
+        if !self.is_shader_hot_reload || now - self.last_shader_poll < SHADER_POLL_INTERVAL { return; }
+        self.last_shader_poll = now;
+
+        unsafe {
+            self.renderer.reload_shaders();
+            if let Some(post_process) = &mut self.post_process { post_process.reload_shaders(); }
+        }
+    }
+
+    unsafe fn draw_frame(&mut self, alpha: f32) -> Result<(), GLError> {
+        self.update_camera_buffer();
+
+        if let Some(gpu_time) = self.gpu_timer.begin() { self.stats.push_gpu_time(gpu_time); }
+        let ret = self.draw_frame_untimed(alpha);
+        self.gpu_timer.end();
+
+        ret
+    }
+
+    // 所有程序共享的摄像机 uniform 块
+    unsafe fn update_camera_buffer(&mut self) {
+        let (Some(buffer), Some(camera)) = (&mut self.camera_buffer, self.renderer.getCamera()) else { return };
+
+        buffer.update(&CameraBlock::from_camera(&camera.borrow()));
+    }
+
+    unsafe fn draw_frame_untimed(&mut self, alpha: f32) -> Result<(), GLError> {
+        match &self.post_process {
+            Some(post_process) => {
+                post_process.begin();
+                self.draw_scene(alpha)?;
+
+                let target_size = self.offscreen.as_ref().map_or(self.size, |fb| fb.size());
+                post_process.end(self.offscreen.as_ref(), target_size)?;
+            },
+            None => {
+                if let Some(target) = &self.offscreen { target.bind(); }
+                self.draw_scene(alpha)?;
+            },
+        }
+        Ok(())
+    }
+
+    // 线框模式只作用于场景，不影响后处理的全屏四边形
+    unsafe fn draw_scene(&self, alpha: f32) -> Result<(), GLError> {
+        if self.is_wireframe { gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE); }
+        let ret = self.renderer.draw_interpolated(alpha);
+        if self.is_wireframe { gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL); }
+
+        ret
+    }
+
+    /**
+     * 读回当前帧的像素
+     * 需要在 draw 之后、swap_buffers 之前调用，否则默认帧缓冲的内容是未定义的
+     */
+    pub fn capture(&self) -> RgbaImage {
+        unsafe {
+            let size = match &self.offscreen {
+                Some(target) => {
+                    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, target.id);
+                    target.size()
+                },
+                None => {
+                    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+                    gl::ReadBuffer(gl::BACK);
+                    self.size
+                },
+            };
+            gl::Finish();
+
+            utility::read_pixels(size)
+        }
+    }
+
+    // 保存截图，格式由扩展名决定（png、jpg 等）
+    pub fn save_screenshot<P: AsRef<Path>>(&self, path: P) -> Result<(), GLError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() { std::fs::create_dir_all(dir)?; }
+
+        let img = self.capture();
+        match path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_lowercase()).as_deref() {
+            // JPEG 不支持透明通道
+            Some("jpg") | Some("jpeg") => image::DynamicImage::ImageRgba8(img).to_rgb8().save(path)?,
+            _ => img.save(path)?,
+        }
+
+        Ok(())
+    }
+
+    // 在下一帧绘制完成后保存截图
+    pub fn request_screenshot<P: AsRef<Path>>(&mut self, path: P) {
+        self.screenshot_request = Some(path.as_ref().to_path_buf());
+    }
+
+    // 设置按 F12 截图时的保存目录
+    pub fn set_screenshot_dir<P: AsRef<Path>>(&mut self, dir: P) {
+        self.screenshot_dir = dir.as_ref().to_path_buf();
     }
 
-    #[allow(clippy::single_match)]
     pub fn execute(&mut self) -> Result<(), GLError> {
         unsafe { self.renderer.pre_draw()?; }
+        self.last_frame = self.glfw.get_time() as f32;
         
         while !self.window.should_close() {
+            let frame_start = Instant::now();
             let current_frame = self.glfw.get_time() as f32;
-            self.delta_time = current_frame - self.last_frame;
-            self.last_frame = current_frame;
 
+            self.input.begin_frame();
             self.glfw.poll_events();
-            for(_, event) in glfw::flush_messages(&self.event_receiver) {
+            let events: Vec<(f64, WindowEvent)> = glfw::flush_messages(&self.event_receiver).collect();
+            for(_, event) in events {
+                self.input.handle_event(&event);
+
                 match event {
                     WindowEvent::CursorPos(x_pos, y_pos) => {
                         let x_pos = x_pos as f32;
@@ -69,29 +429,89 @@ impl<T: IRenderer> Engine<T> {
                         self.cursor_pos.0 = x_pos;
                         self.cursor_pos.1 = y_pos;
                 
-                        self.renderer.getCamera().unwrap().borrow_mut().process_mouse_move(x_offset, y_offset);
+                        if let Some(camera) = self.renderer.getCamera().filter(|_| self.camera_path.is_none()) { 
+                            let mut camera = camera.borrow_mut();
+                            match camera.get_mode() {
+                                CameraMode::Fly => camera.process_mouse_move(x_offset, y_offset),
+                                // 环绕模式：左键拖拽旋转，右键或中键拖拽平移
+                                CameraMode::Orbit => {
+                                    if self.input.is_mouse_down(MouseButton::Button1) {
+                                        camera.process_mouse_move(x_offset, y_offset);
+                                    } else if self.input.is_mouse_down(MouseButton::Button2) || self.input.is_mouse_down(MouseButton::Button3) {
+                                        camera.process_mouse_pan(x_offset, y_offset);
+                                    }
+                                },
+                            }
+                        }
+                        self.renderer.on_cursor_move(x_pos, y_pos);
+                    },
+                    WindowEvent::Scroll(x_offset, y_offset) => {
+                        if let Some(camera) = self.renderer.getCamera().filter(|_| self.camera_path.is_none()) { camera.borrow_mut().process_mouse_scroll(y_offset as f32); }
+                        self.renderer.on_scroll(x_offset as f32, y_offset as f32);
                     },
+                    WindowEvent::Key(key, _, action, mods) => self.renderer.on_key(key, action, mods),
+                    WindowEvent::MouseButton(button, action, mods) => self.renderer.on_mouse_button(button, action, mods),
+                    WindowEvent::Char(c) => self.renderer.on_char(c),
+                    WindowEvent::FramebufferSize(width, height) => self.handle_resize((width, height))?,
                     _ => (),
                 }
             }
+            self.renderer.on_input(&self.input);
+            self.poll_shaders(current_frame);
+
+            let alpha = self.step(current_frame - self.last_frame);
+            self.stats.push_frame_time((current_frame - self.last_frame) * 1000.0);
+            self.last_frame = current_frame;
 
-            // 不在事件里处理键盘事件，是因为不能持续触发
+            // 持续按住的移动与只触发一次的动作都根据本帧的输入状态处理
             self.handle_keyboard();
 
-            unsafe { self.renderer.draw()?; }
+            unsafe { self.draw_frame(alpha)?; }
+            self.stats.push_cpu_time(frame_start.elapsed().as_secs_f32() * 1000.0);
+            self.update_title(current_frame);
+
+            if let Some(path) = self.screenshot_request.take() {
+                if let Err(err) = self.save_screenshot(&path) { eprintln!("Failed to save screenshot {}: {}", path.display(), err); }
+            }
+
             self.window.swap_buffers();
+
+            // 限制帧率，剩余的时间休眠
+            if let Some(fps) = self.frame_rate_limit {
+                let remaining = 1.0 / fps - (self.glfw.get_time() as f32 - current_frame);
+                if remaining > 0.0 { std::thread::sleep(std::time::Duration::from_secs_f32(remaining)); }
+            }
         }
         Ok(())
     }
 
     fn handle_keyboard(&mut self) {
-        if self.renderer.getCamera().is_some() {
-            if self.window.get_key(Key::W) == Action::Press { self.renderer.getCamera().unwrap().borrow_mut().process_keyboard(CameraMovement::Forward, self.delta_time) }
-            if self.window.get_key(Key::S) == Action::Press { self.renderer.getCamera().unwrap().borrow_mut().process_keyboard(CameraMovement::Bakcward, self.delta_time) }
-            if self.window.get_key(Key::A) == Action::Press { self.renderer.getCamera().unwrap().borrow_mut().process_keyboard(CameraMovement::Left, self.delta_time) }
-            if self.window.get_key(Key::D) == Action::Press { self.renderer.getCamera().unwrap().borrow_mut().process_keyboard(CameraMovement::Right, self.delta_time) }
+        if let Some(camera) = self.renderer.getCamera().filter(|_| self.camera_path.is_none()) {
+            let mut camera = camera.borrow_mut();
+
+            // 加速、减速
+            let speed_multiplier = match (self.input.is_action_down(input::SPEED_FAST), self.input.is_action_down(input::SPEED_SLOW)) {
+                (true, false) => FAST_SPEED_MULTIPLIER,
+                (false, true) => SLOW_SPEED_MULTIPLIER,
+                _ => 1.0,
+            };
+            camera.set_speed_multiplier(speed_multiplier);
 
-            if self.window.get_key(Key::Space) == Action::Press { 
+            if self.input.is_action_down(input::MOVE_FORWARD) { camera.process_keyboard(CameraMovement::Forward, self.delta_time) }
+            if self.input.is_action_down(input::MOVE_BACKWARD) { camera.process_keyboard(CameraMovement::Bakcward, self.delta_time) }
+            if self.input.is_action_down(input::MOVE_LEFT) { camera.process_keyboard(CameraMovement::Left, self.delta_time) }
+            if self.input.is_action_down(input::MOVE_RIGHT) { camera.process_keyboard(CameraMovement::Right, self.delta_time) }
+            if self.input.is_action_down(input::MOVE_UP) { camera.process_keyboard(CameraMovement::Up, self.delta_time) }
+            if self.input.is_action_down(input::MOVE_DOWN) { camera.process_keyboard(CameraMovement::Down, self.delta_time) }
+            if self.input.is_action_down(input::ROLL_LEFT) { camera.process_keyboard(CameraMovement::RollLeft, self.delta_time) }
+            if self.input.is_action_down(input::ROLL_RIGHT) { camera.process_keyboard(CameraMovement::RollRight, self.delta_time) }
+
+            if self.input.is_action_pressed(input::TOGGLE_FREE_LOOK) { 
+                let is_free_look = camera.is_free_look();
+                camera.set_free_look(!is_free_look); 
+            }
+
+            if self.input.is_action_pressed(input::TOGGLE_CURSOR) { 
                 match self.window.get_cursor_mode() {
                     CursorMode::Disabled => self.window.set_cursor_mode(CursorMode::Normal),
                     CursorMode::Normal => self.window.set_cursor_mode(CursorMode::Disabled),
@@ -100,6 +520,20 @@ impl<T: IRenderer> Engine<T> {
             }
         }
 
-        if self.window.get_key(Key::Escape) == Action::Press { self.window.set_should_close(true) }
+        if self.input.is_action_pressed(input::SCREENSHOT) {
+            let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+            self.screenshot_request = Some(self.screenshot_dir.join(format!("screenshot-{}.png", millis)));
+        }
+
+        if self.input.is_action_pressed(input::TOGGLE_WIREFRAME) { self.is_wireframe = !self.is_wireframe; }
+
+        // 切换后处理效果
+        if let Some(post_process) = &mut self.post_process {
+            for i in 0..9 {
+                if self.input.is_action_pressed(&format!("{}{}", input::TOGGLE_EFFECT, i + 1)) { post_process.toggle(i); }
+            }
+        }
+
+        if self.input.is_action_down(input::QUIT) { self.window.set_should_close(true) }
     }
 }
\ No newline at end of file
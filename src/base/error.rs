@@ -1,6 +1,7 @@
 use std::ffi::NulError;
 use std::string::FromUtf8Error;
 
+use gl::types::GLenum;
 use image::ImageError;
 use thiserror::Error as ThisError;
 
@@ -13,8 +14,32 @@ pub enum GLError {
     #[error("Glfw init error.")]
     GlfwError(#[from] glfw::InitError),
 
+    #[error("Failed to create GLFW window.")]
+    WindowCreationError,
+
     #[error("An error occurred while loading the model.")]
     ModelError(#[from] ModelError),
+
+    #[error("Framebuffer is incomplete, status: {0:#x}.")]
+    FramebufferIncomplete(GLenum),
+
+    #[error("Error occurred while reading or writing image.")]
+    ImageError(#[from] ImageError),
+
+    #[error("An error occurred while reading or writing the file.")]
+    IoError(#[from] std::io::Error),
+
+    #[error("An error occurred while serializing or deserializing JSON.")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("An error occurred while loading the input bindings.")]
+    InputError(#[from] InputError),
+
+    #[error("Rendered image does not match {0}: {1} pixels differ, max delta {2}.")]
+    GoldenImageMismatch(String, usize, u8),
+
+    #[error("Reference image {0} does not exist, set OPENGL_RS_UPDATE_GOLDEN=1 to create it.")]
+    GoldenImageMissing(String),
 }
 
 #[allow(dead_code)]
@@ -30,6 +55,18 @@ pub enum ModelError {
     TextureLoadError(#[from] ImageError),
 }
 
+#[derive(Debug, ThisError)]
+pub enum InputError {
+    #[error("Unknown key or mouse button: {0}.")]
+    UnknownBinding(String),
+
+    #[error("Syntax error at line {0}: {1}")]
+    SyntaxError(usize, String),
+
+    #[error("An error occurred while reading the file.")]
+    IoError(#[from] std::io::Error),
+}
+
 #[allow(clippy::enum_variant_names)]
 #[allow(dead_code)]
 #[derive(Debug, ThisError)]
@@ -40,12 +77,36 @@ pub enum ShaderError {
     #[error("Error while linking shaders: {0}")]
     LinkingError(String),
 
+    #[error("Error while preprocessing shader: {0}")]
+    PreprocessError(String),
+
+    #[error("The {0} shader stage requires OpenGL {1}.{2}, but the current context is OpenGL {3}.{4}.")]
+    UnsupportedStage(&'static str, u32, u32, u32, u32),
+
+    #[error("Invalid combination of shader stages: {0}")]
+    InvalidStages(String),
+
     #[error("Cannot find the location index of the property variable named {0}.")]
     AttributeLocationParseError(String),
 
     #[error("Cannot find the location index of the uniform variable named {0}.")]
     UniformLocationParseError(String),
 
+    #[error("The uniform variable {0} has GLSL type {1}, which cannot be set from {2}.")]
+    UniformTypeMismatch(String, &'static str, &'static str),
+
+    #[error("The uniform variable {0} expects {1} components, but {2} were given.")]
+    UniformValueLength(String, usize, usize),
+
+    #[error("The uniform block {0} does not match the buffer layout: {1}.")]
+    UniformBlockMismatch(String, String),
+
+    #[error("Cannot bind the uniform block {0}: all {1} uniform buffer binding points are in use.")]
+    TooManyUniformBlocks(String, u32),
+
+    #[error("Cannot set {1} elements of the uniform array {0}, which only has {2} elements from this index.")]
+    UniformArrayOverflow(String, usize, usize),
+
     #[error{"{0}"}]
     Utf8Error(#[from] FromUtf8Error),
     
@@ -0,0 +1,210 @@
+#![allow(dead_code)]
+
+use gl::types::{GLuint, GLenum, GLint, GLbitfield};
+
+use crate::base::error::GLError;
+
+/**
+ * 颜色附件的格式
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorFormat {
+    pub internal_format: GLenum,
+    pub format: GLenum,
+    pub data_type: GLenum,
+    pub filter: GLenum,                     // 整数格式只能使用 NEAREST
+}
+
+impl ColorFormat {
+    pub const RGBA8: Self = Self { internal_format: gl::RGBA8, format: gl::RGBA, data_type: gl::UNSIGNED_BYTE, filter: gl::LINEAR };
+    pub const RGBA16F: Self = Self { internal_format: gl::RGBA16F, format: gl::RGBA, data_type: gl::FLOAT, filter: gl::LINEAR };
+    pub const R32UI: Self = Self { internal_format: gl::R32UI, format: gl::RED_INTEGER, data_type: gl::UNSIGNED_INT, filter: gl::NEAREST };
+    pub const RG32UI: Self = Self { internal_format: gl::RG32UI, format: gl::RG_INTEGER, data_type: gl::UNSIGNED_INT, filter: gl::NEAREST };
+}
+
+/**
+ * 深度、模板附件
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DepthStencil { None, Depth, DepthStencil }
+
+pub struct Framebuffer {
+    pub id: GLuint,
+    size: (u32, u32),
+
+    color_formats: Vec<ColorFormat>,
+    color_textures: Vec<GLuint>,            // 颜色附件，依次绑定到 COLOR_ATTACHMENT0..n
+
+    depth_stencil: DepthStencil,
+    depth_rbo: GLuint,                      // 深度、模板渲染缓冲
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        unsafe {
+            self.delete_attachments();
+            gl::DeleteFramebuffers(1, [self.id].as_ptr());
+        }
+    }
+}
+
+impl Framebuffer {
+    pub unsafe fn new(size: (u32, u32), color_formats: &[ColorFormat], depth_stencil: DepthStencil) -> Result<Self, GLError> {
+        let mut ret = Self { 
+            id: 0, 
+            size, 
+            color_formats: color_formats.to_vec(), 
+            color_textures: Vec::new(), 
+            depth_stencil, 
+            depth_rbo: 0 
+        };
+
+        gl::GenFramebuffers(1, &mut ret.id);
+        ret.create_attachments()?;
+
+        Ok(ret)
+    }
+
+    pub fn size(&self) -> (u32, u32) { self.size }
+
+    // 第 index 个颜色附件的纹理 ID
+    pub fn color_texture(&self, index: usize) -> GLuint { self.color_textures[index] }
+
+    pub unsafe fn bind(&self) { 
+        gl::BindFramebuffer(gl::FRAMEBUFFER, self.id); 
+        gl::Viewport(0, 0, self.size.0 as GLint, self.size.1 as GLint);
+    }
+
+    // 切换回默认帧缓冲
+    pub unsafe fn unbind(&self) { gl::BindFramebuffer(gl::FRAMEBUFFER, 0); }
+
+    // 将颜色附件绑定到纹理单元上，供后续的着色器采样
+    pub unsafe fn activate_color(&self, index: usize, unit: GLenum) {
+        gl::ActiveTexture(unit);
+        gl::BindTexture(gl::TEXTURE_2D, self.color_textures[index]);
+    }
+
+    // 重新分配所有附件的存储空间
+    pub unsafe fn resize(&mut self, size: (u32, u32)) -> Result<(), GLError> {
+        if size == self.size { return Ok(()); }
+
+        self.size = size;
+        self.delete_attachments();
+        self.create_attachments()
+    }
+
+    /**
+     * 将本帧缓冲的内容复制到目标帧缓冲，target 为 None 时复制到默认帧缓冲
+     * mask: COLOR_BUFFER_BIT、DEPTH_BUFFER_BIT、STENCIL_BUFFER_BIT 的组合
+     */
+    pub unsafe fn blit_to(&self, target: Option<&Framebuffer>, target_size: (u32, u32), mask: GLbitfield, filter: GLenum) {
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.id);
+        gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, target.map_or(0, |fb| fb.id));
+
+        gl::BlitFramebuffer(
+            0, 
+            0, 
+            self.size.0 as GLint, 
+            self.size.1 as GLint, 
+            0, 
+            0, 
+            target_size.0 as GLint, 
+            target_size.1 as GLint, 
+            mask, 
+            filter
+        );
+
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+    }
+
+    // 复制颜色附件到默认帧缓冲
+    pub unsafe fn blit_to_default(&self, window_size: (u32, u32)) {
+        self.blit_to(None, window_size, gl::COLOR_BUFFER_BIT, gl::LINEAR);
+    }
+
+    // 检查帧缓冲的完整性
+    pub unsafe fn check_status(&self) -> Result<(), GLError> {
+        gl::BindFramebuffer(gl::FRAMEBUFFER, self.id);
+        let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+
+        match status {
+            gl::FRAMEBUFFER_COMPLETE => Ok(()),
+            status => Err(GLError::FramebufferIncomplete(status)),
+        }
+    }
+
+    unsafe fn create_attachments(&mut self) -> Result<(), GLError> {
+        let (width, height) = (self.size.0 as GLint, self.size.1 as GLint);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, self.id);
+
+        // 颜色附件
+        let mut draw_buffers: Vec<GLenum> = Vec::with_capacity(self.color_formats.len());
+        for (i, format) in self.color_formats.iter().enumerate() {
+            let mut texture: GLuint = 0;
+            gl::GenTextures(1, &mut texture);
+            gl::BindTexture(gl::TEXTURE_2D, texture);
+
+            gl::TexImage2D(
+                gl::TEXTURE_2D, 
+                0, 
+                format.internal_format as GLint, 
+                width, 
+                height, 
+                0, 
+                format.format, 
+                format.data_type, 
+                std::ptr::null()
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, format.filter as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, format.filter as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as GLint);
+
+            let attachment = gl::COLOR_ATTACHMENT0 + i as GLenum;
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, attachment, gl::TEXTURE_2D, texture, 0);
+
+            self.color_textures.push(texture);
+            draw_buffers.push(attachment);
+        }
+        gl::BindTexture(gl::TEXTURE_2D, 0);
+
+        if draw_buffers.is_empty() {
+            gl::DrawBuffer(gl::NONE);
+            gl::ReadBuffer(gl::NONE);
+        } else {
+            gl::DrawBuffers(draw_buffers.len() as GLint, draw_buffers.as_ptr());
+            gl::ReadBuffer(gl::COLOR_ATTACHMENT0);
+        }
+
+        // 深度、模板附件
+        let depth = match self.depth_stencil {
+            DepthStencil::None => None,
+            DepthStencil::Depth => Some((gl::DEPTH_COMPONENT24, gl::DEPTH_ATTACHMENT)),
+            DepthStencil::DepthStencil => Some((gl::DEPTH24_STENCIL8, gl::DEPTH_STENCIL_ATTACHMENT)),
+        };
+        if let Some((internal_format, attachment)) = depth {
+            gl::GenRenderbuffers(1, &mut self.depth_rbo);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, self.depth_rbo);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, internal_format, width, height);
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, attachment, gl::RENDERBUFFER, self.depth_rbo);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+        }
+
+        let ret = self.check_status();
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+
+        ret
+    }
+
+    unsafe fn delete_attachments(&mut self) {
+        if !self.color_textures.is_empty() {
+            gl::DeleteTextures(self.color_textures.len() as GLint, self.color_textures.as_ptr());
+            self.color_textures.clear();
+        }
+
+        if self.depth_rbo != 0 {
+            gl::DeleteRenderbuffers(1, [self.depth_rbo].as_ptr());
+            self.depth_rbo = 0;
+        }
+    }
+}
@@ -0,0 +1,167 @@
+#![allow(dead_code)]
+
+use nalgebra_glm as glm;
+
+/**
+ * 轴对齐包围盒（Axis-Aligned Bounding Box）
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: glm::Vec3,
+    pub max: glm::Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: glm::Vec3, max: glm::Vec3) -> Self { Self { min, max } }
+
+    // 包含所有点的最小包围盒，没有点时返回 None
+    pub fn from_points<I: IntoIterator<Item = glm::Vec3>>(points: I) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+
+        Some(points.fold(Self::new(first, first), |aabb, p| Self::new(glm::min2(&aabb.min, &p), glm::max2(&aabb.max, &p))))
+    }
+
+    pub fn center(&self) -> glm::Vec3 { (self.min + self.max) * 0.5 }
+
+    // 半边长
+    pub fn extents(&self) -> glm::Vec3 { (self.max - self.min) * 0.5 }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Self::new(glm::min2(&self.min, &other.min), glm::max2(&self.max, &other.max))
+    }
+
+    // 变换后重新计算包围盒，结果会比原来的包围盒更松
+    pub fn transform(&self, matrix: &glm::Mat4) -> Aabb {
+        let center = matrix.transform_point(&self.center().into()).coords;
+        let abs = matrix.fixed_view::<3, 3>(0, 0).abs();
+        let extents = abs * self.extents();
+
+        Self::new(center - extents, center + extents)
+    }
+}
+
+/**
+ * 视锥体，由 6 个朝内的平面组成，平面以 (a, b, c, d) 表示 ax + by + cz + d = 0
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    pub planes: [glm::Vec4; 6],             // 左、右、下、上、近、远
+}
+
+impl Frustum {
+    /**
+     * 从投影矩阵（Gribb-Hartmann 方法）提取平面
+     * 传入 projection * view 得到世界空间的视锥体，再乘上 model 则得到模型空间的视锥体
+     */
+    pub fn from_matrix(m: &glm::Mat4) -> Self {
+        let row = |i: usize| glm::vec4(m[(i, 0)], m[(i, 1)], m[(i, 2)], m[(i, 3)]);
+        let (r0, r1, r2, r3) = (row(0), row(1), row(2), row(3));
+
+        let mut planes = [r3 + r0, r3 - r0, r3 + r1, r3 - r1, r3 + r2, r3 - r2];
+        for plane in &mut planes {
+            let len = glm::length(&plane.xyz());
+            if len > 0.0 { *plane /= len; }
+        }
+
+        Self { planes }
+    }
+
+    pub fn contains_point(&self, point: &glm::Vec3) -> bool {
+        self.planes.iter().all(|p| glm::dot(&p.xyz(), point) + p.w >= 0.0)
+    }
+
+    // 包围盒与视锥体相交或在视锥体内，只检查沿平面法线最远的顶点
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes.iter().all(|p| {
+            let positive = glm::vec3(
+                if p.x >= 0.0 { aabb.max.x } else { aabb.min.x },
+                if p.y >= 0.0 { aabb.max.y } else { aabb.min.y },
+                if p.z >= 0.0 { aabb.max.z } else { aabb.min.z },
+            );
+            glm::dot(&p.xyz(), &positive) + p.w >= 0.0
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 位于原点、朝向 -z、视野 90° 的摄像机
+    fn frustum() -> Frustum {
+        let projection = glm::perspective(1.0, 90_f32.to_radians(), 0.1, 100.0);
+        Frustum::from_matrix(&projection)
+    }
+
+    fn cube(center: glm::Vec3, half: f32) -> Aabb {
+        Aabb::new(center - glm::vec3(half, half, half), center + glm::vec3(half, half, half))
+    }
+
+    #[test]
+    fn planes_are_normalized() {
+        for plane in frustum().planes {
+            assert!((glm::length(&plane.xyz()) - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn contains_points_inside_only() {
+        let frustum = frustum();
+
+        assert!(frustum.contains_point(&glm::vec3(0.0, 0.0, -5.0)));
+        assert!(frustum.contains_point(&glm::vec3(4.9, -4.9, -5.0)));
+        assert!(!frustum.contains_point(&glm::vec3(5.1, 0.0, -5.0)));
+        assert!(!frustum.contains_point(&glm::vec3(0.0, 0.0, 5.0)));
+        assert!(!frustum.contains_point(&glm::vec3(0.0, 0.0, -0.05)));
+        assert!(!frustum.contains_point(&glm::vec3(0.0, 0.0, -101.0)));
+    }
+
+    #[test]
+    fn aabb_inside_and_outside() {
+        let frustum = frustum();
+
+        assert!(frustum.intersects_aabb(&cube(glm::vec3(0.0, 0.0, -10.0), 1.0)));
+        // 在摄像机后方
+        assert!(!frustum.intersects_aabb(&cube(glm::vec3(0.0, 0.0, 10.0), 1.0)));
+        // 在左侧平面之外
+        assert!(!frustum.intersects_aabb(&cube(glm::vec3(-20.0, 0.0, -10.0), 1.0)));
+        // 超出远平面
+        assert!(!frustum.intersects_aabb(&cube(glm::vec3(0.0, 0.0, -200.0), 1.0)));
+    }
+
+    #[test]
+    fn aabb_straddling_a_plane_intersects() {
+        let frustum = frustum();
+
+        // 跨过右侧平面 x = -z
+        assert!(frustum.intersects_aabb(&cube(glm::vec3(10.0, 0.0, -10.0), 1.0)));
+        // 跨过近平面
+        assert!(frustum.intersects_aabb(&cube(glm::vec3(0.0, 0.0, 0.0), 0.5)));
+        // 包含整个视锥体
+        assert!(frustum.intersects_aabb(&cube(glm::vec3(0.0, 0.0, 0.0), 500.0)));
+    }
+
+    #[test]
+    fn world_space_frustum_follows_view() {
+        // 摄像机在 (0, 0, 10) 朝向 -z
+        let view = glm::look_at(&glm::vec3(0.0, 0.0, 10.0), &glm::vec3(0.0, 0.0, 0.0), &glm::vec3(0.0, 1.0, 0.0));
+        let frustum = Frustum::from_matrix(&(glm::perspective(1.0, 90_f32.to_radians(), 0.1, 100.0) * view));
+
+        assert!(frustum.intersects_aabb(&cube(glm::vec3(0.0, 0.0, 0.0), 1.0)));
+        assert!(!frustum.intersects_aabb(&cube(glm::vec3(0.0, 0.0, 20.0), 1.0)));
+    }
+
+    #[test]
+    fn transformed_aabb_contains_rotated_corners() {
+        let aabb = cube(glm::Vec3::zeros(), 1.0);
+        let matrix = glm::translate(&glm::Mat4::identity(), &glm::vec3(5.0, 0.0, 0.0));
+        let matrix = glm::rotate(&matrix, 45_f32.to_radians(), &glm::vec3(0.0, 0.0, 1.0));
+        let moved = aabb.transform(&matrix);
+
+        let half = 2_f32.sqrt();
+        assert!(glm::distance(&moved.min, &glm::vec3(5.0 - half, -half, -1.0)) < 1e-5);
+        assert!(glm::distance(&moved.max, &glm::vec3(5.0 + half, half, 1.0)) < 1e-5);
+    }
+}
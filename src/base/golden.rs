@@ -0,0 +1,185 @@
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use glfw::ContextCreationApi;
+use image::{Rgba, RgbaImage};
+
+use crate::IRenderer;
+use crate::base::{engine::Engine, error::GLError};
+
+// 为 true 时用本次的渲染结果覆盖基准图像
+const UPDATE_ENV: &str = "OPENGL_RS_UPDATE_GOLDEN";
+// 无窗口模式下上下文的创建方式：native / egl / osmesa，都需要 X11 或 Wayland 显示
+const CONTEXT_API_ENV: &str = "OPENGL_RS_CONTEXT_API";
+
+/**
+ * 两张图像的比较结果
+ */
+#[derive(Debug)]
+pub struct ImageDiff {
+    pub mismatched: usize,                  // 超出容差的像素个数
+    pub max_delta: u8,                      // 所有通道中最大的差值
+    pub image: RgbaImage,                   // 差异图，超出容差的像素标红
+}
+
+/**
+ * 基准图像（golden image）回归测试
+ */
+pub struct GoldenTest {
+    golden_path: PathBuf,
+    size: (u32, u32),
+    frames: u32,                            // 渲染的帧数，读回最后一帧
+    tolerance: u8,                          // 单个通道允许的最大差值
+    max_mismatched: usize,                  // 允许超出容差的像素个数
+}
+
+impl GoldenTest {
+    pub fn new<P: AsRef<Path>>(golden_path: P, size: (u32, u32)) -> Self {
+        Self { golden_path: golden_path.as_ref().to_path_buf(), size, frames: 1, tolerance: 2, max_mismatched: 0 }
+    }
+
+    pub fn frames(mut self, frames: u32) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn max_mismatched(mut self, max_mismatched: usize) -> Self {
+        self.max_mismatched = max_mismatched;
+        self
+    }
+
+    /**
+     * 在无窗口模式下渲染，并与基准图像比较
+     * 设置了 OPENGL_RS_UPDATE_GOLDEN=1 时，写入本次的渲染结果
+     * 基准图像不存在时返回错误，比较失败时，在基准图像旁写入 *.actual.png 与 *.diff.png
+     */
+    pub fn run<T: IRenderer, F: Fn() -> Result<T, GLError>>(&self, act: F) -> Result<(), GLError> {
+        let mut engine = Engine::new_headless(self.size, context_api_from_env(), act)?;
+        let actual = engine.render_frames(self.frames)?;
+
+        if std::env::var(UPDATE_ENV).is_ok_and(|v| v == "1") {
+            if let Some(dir) = self.golden_path.parent() { std::fs::create_dir_all(dir)?; }
+            actual.save(&self.golden_path)?;
+            return Ok(());
+        }
+
+        // 基准图像缺失时不能视为通过，否则干净的检出上测试永远不会失败
+        if !self.golden_path.exists() {
+            actual.save(self.golden_path.with_extension("actual.png"))?;
+            return Err(GLError::GoldenImageMissing(self.golden_path.display().to_string()));
+        }
+
+        let expected = image::open(&self.golden_path)?.to_rgba8();
+        let diff = compare_images(&actual, &expected, self.tolerance);
+
+        if diff.mismatched <= self.max_mismatched {
+            return Ok(());
+        }
+
+        actual.save(self.golden_path.with_extension("actual.png"))?;
+        diff.image.save(self.golden_path.with_extension("diff.png"))?;
+
+        Err(GLError::GoldenImageMismatch(self.golden_path.display().to_string(), diff.mismatched, diff.max_delta))
+    }
+}
+
+// 逐像素比较两张图像，尺寸不同时全部视为不匹配
+pub fn compare_images(actual: &RgbaImage, expected: &RgbaImage, tolerance: u8) -> ImageDiff {
+    let (width, height) = actual.dimensions();
+
+    if expected.dimensions() != (width, height) {
+        let image = RgbaImage::from_pixel(width, height, Rgba([255, 0, 0, 255]));
+        return ImageDiff { mismatched: (width * height) as usize, max_delta: u8::MAX, image };
+    }
+
+    let mut mismatched = 0;
+    let mut max_delta = 0;
+    let mut image = RgbaImage::new(width, height);
+
+    for (x, y, a) in actual.enumerate_pixels() {
+        let e = expected.get_pixel(x, y);
+        let delta = a.0.iter().zip(e.0.iter()).map(|(a, e)| a.abs_diff(*e)).max().unwrap_or(0);
+        max_delta = max_delta.max(delta);
+
+        let pixel = if delta > tolerance {
+            mismatched += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            // 匹配的像素以变暗的灰度显示，方便定位差异
+            let luma = ((a[0] as u32 + a[1] as u32 + a[2] as u32) / 12) as u8;
+            Rgba([luma, luma, luma, 255])
+        };
+        image.put_pixel(x, y, pixel);
+    }
+
+    ImageDiff { mismatched, max_delta, image }
+}
+
+// 按 OPENGL_RS_CONTEXT_API 选择上下文的创建方式，只影响上下文，不能代替显示设备
+pub fn context_api_from_env() -> ContextCreationApi {
+    match std::env::var(CONTEXT_API_ENV).unwrap_or_default().to_lowercase().as_str() {
+        "egl" => ContextCreationApi::Egl,
+        "osmesa" => ContextCreationApi::OsMesa,
+        _ => ContextCreationApi::Native,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(pixels: &[[u8; 4]], width: u32) -> RgbaImage {
+        let raw = pixels.iter().flatten().copied().collect();
+        RgbaImage::from_raw(width, pixels.len() as u32 / width, raw).unwrap()
+    }
+
+    #[test]
+    fn identical_images_match() {
+        let a = image(&[[10, 20, 30, 255], [40, 50, 60, 255]], 2);
+        let diff = compare_images(&a, &a.clone(), 0);
+
+        assert_eq!(diff.mismatched, 0);
+        assert_eq!(diff.max_delta, 0);
+        assert_eq!(diff.image.dimensions(), (2, 1));
+    }
+
+    #[test]
+    fn deltas_within_tolerance_match() {
+        let a = image(&[[10, 20, 30, 255], [40, 50, 60, 255]], 2);
+        let b = image(&[[12, 20, 30, 255], [40, 48, 60, 254]], 2);
+        let diff = compare_images(&a, &b, 2);
+
+        assert_eq!(diff.mismatched, 0);
+        assert_eq!(diff.max_delta, 2);
+    }
+
+    #[test]
+    fn deltas_over_tolerance_are_marked_red() {
+        let a = image(&[[10, 20, 30, 255], [40, 50, 60, 255]], 2);
+        let b = image(&[[13, 20, 30, 255], [40, 50, 60, 255]], 2);
+        let diff = compare_images(&a, &b, 2);
+
+        assert_eq!(diff.mismatched, 1);
+        assert_eq!(diff.max_delta, 3);
+        assert_eq!(*diff.image.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+        assert_ne!(*diff.image.get_pixel(1, 0), Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn size_mismatch_fails_every_pixel() {
+        let a = RgbaImage::new(4, 2);
+        let b = RgbaImage::new(2, 4);
+        let diff = compare_images(&a, &b, u8::MAX);
+
+        assert_eq!(diff.mismatched, 8);
+        assert_eq!(diff.max_delta, u8::MAX);
+        assert_eq!(diff.image.dimensions(), (4, 2));
+        assert!(diff.image.pixels().all(|p| *p == Rgba([255, 0, 0, 255])));
+    }
+}
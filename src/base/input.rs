@@ -0,0 +1,390 @@
+#![allow(dead_code)]
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use glfw::{Action, Key, MouseButton, WindowEvent};
+
+use crate::base::error::InputError;
+
+// 配置文件中可以使用的按键名称，不区分大小写
+const KEY_NAMES: &[(&str, Key)] = &[
+    ("A", Key::A), ("B", Key::B), ("C", Key::C), ("D", Key::D), ("E", Key::E), ("F", Key::F), ("G", Key::G),
+    ("H", Key::H), ("I", Key::I), ("J", Key::J), ("K", Key::K), ("L", Key::L), ("M", Key::M), ("N", Key::N),
+    ("O", Key::O), ("P", Key::P), ("Q", Key::Q), ("R", Key::R), ("S", Key::S), ("T", Key::T), ("U", Key::U),
+    ("V", Key::V), ("W", Key::W), ("X", Key::X), ("Y", Key::Y), ("Z", Key::Z),
+    ("0", Key::Num0), ("1", Key::Num1), ("2", Key::Num2), ("3", Key::Num3), ("4", Key::Num4),
+    ("5", Key::Num5), ("6", Key::Num6), ("7", Key::Num7), ("8", Key::Num8), ("9", Key::Num9),
+    ("F1", Key::F1), ("F2", Key::F2), ("F3", Key::F3), ("F4", Key::F4), ("F5", Key::F5), ("F6", Key::F6),
+    ("F7", Key::F7), ("F8", Key::F8), ("F9", Key::F9), ("F10", Key::F10), ("F11", Key::F11), ("F12", Key::F12),
+    ("Space", Key::Space), ("Escape", Key::Escape), ("Enter", Key::Enter), ("Tab", Key::Tab),
+    ("Backspace", Key::Backspace), ("Insert", Key::Insert), ("Delete", Key::Delete),
+    ("Up", Key::Up), ("Down", Key::Down), ("Left", Key::Left), ("Right", Key::Right),
+    ("PageUp", Key::PageUp), ("PageDown", Key::PageDown), ("Home", Key::Home), ("End", Key::End),
+    ("LeftShift", Key::LeftShift), ("RightShift", Key::RightShift),
+    ("LeftControl", Key::LeftControl), ("RightControl", Key::RightControl),
+    ("LeftAlt", Key::LeftAlt), ("RightAlt", Key::RightAlt),
+    ("Minus", Key::Minus), ("Equal", Key::Equal), ("Comma", Key::Comma), ("Period", Key::Period),
+    ("Slash", Key::Slash), ("Semicolon", Key::Semicolon), ("Apostrophe", Key::Apostrophe),
+    ("LeftBracket", Key::LeftBracket), ("RightBracket", Key::RightBracket),
+    ("Backslash", Key::Backslash), ("GraveAccent", Key::GraveAccent),
+];
+
+const MOUSE_BUTTON_NAMES: &[(&str, MouseButton)] = &[
+    ("MouseLeft", MouseButton::Button1), 
+    ("MouseRight", MouseButton::Button2), 
+    ("MouseMiddle", MouseButton::Button3),
+];
+
+// 引擎内置的动作
+pub const MOVE_FORWARD: &str = "move_forward";
+pub const MOVE_BACKWARD: &str = "move_backward";
+pub const MOVE_LEFT: &str = "move_left";
+pub const MOVE_RIGHT: &str = "move_right";
+pub const MOVE_UP: &str = "move_up";
+pub const MOVE_DOWN: &str = "move_down";
+pub const ROLL_LEFT: &str = "roll_left";
+pub const ROLL_RIGHT: &str = "roll_right";
+pub const SPEED_FAST: &str = "speed_fast";
+pub const SPEED_SLOW: &str = "speed_slow";
+pub const TOGGLE_FREE_LOOK: &str = "toggle_free_look";
+pub const TOGGLE_CURSOR: &str = "toggle_cursor";
+pub const SCREENSHOT: &str = "screenshot";
+pub const TOGGLE_WIREFRAME: &str = "toggle_wireframe";
+pub const QUIT: &str = "quit";
+// 切换第 n 个后处理效果，n 为 1-9
+pub const TOGGLE_EFFECT: &str = "toggle_effect_";
+
+/**
+ * 触发一个动作的输入
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(Key),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+
+        if let Some((_, key)) = KEY_NAMES.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+            return Some(Binding::Key(*key));
+        }
+        MOUSE_BUTTON_NAMES.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, button)| Binding::Mouse(*button))
+    }
+}
+
+/**
+ * 动作名称到输入的映射，一个动作可以绑定多个输入
+ */
+#[derive(Debug, Clone)]
+pub struct ActionMap {
+    bindings: HashMap<String, Vec<Binding>>,
+}
+
+// 默认包含引擎内置动作的绑定
+impl Default for ActionMap {
+    fn default() -> Self {
+        let mut ret = Self::empty();
+        ret.bind(MOVE_FORWARD, Binding::Key(Key::W));
+        ret.bind(MOVE_BACKWARD, Binding::Key(Key::S));
+        ret.bind(MOVE_LEFT, Binding::Key(Key::A));
+        ret.bind(MOVE_RIGHT, Binding::Key(Key::D));
+        ret.bind(MOVE_UP, Binding::Key(Key::E));
+        ret.bind(MOVE_DOWN, Binding::Key(Key::Q));
+        ret.bind(ROLL_LEFT, Binding::Key(Key::Z));
+        ret.bind(ROLL_RIGHT, Binding::Key(Key::C));
+        ret.bind(SPEED_FAST, Binding::Key(Key::LeftShift));
+        ret.bind(SPEED_SLOW, Binding::Key(Key::LeftControl));
+        ret.bind(TOGGLE_FREE_LOOK, Binding::Key(Key::F2));
+        ret.bind(TOGGLE_CURSOR, Binding::Key(Key::Space));
+        ret.bind(SCREENSHOT, Binding::Key(Key::F12));
+        ret.bind(TOGGLE_WIREFRAME, Binding::Key(Key::F1));
+        ret.bind(QUIT, Binding::Key(Key::Escape));
+
+        const EFFECT_KEYS: [Key; 9] = [Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9];
+        for (i, key) in EFFECT_KEYS.iter().enumerate() {
+            ret.bind(format!("{}{}", TOGGLE_EFFECT, i + 1), Binding::Key(*key));
+        }
+
+        ret
+    }
+}
+
+impl ActionMap {
+    // 没有任何绑定
+    pub fn empty() -> Self { Self { bindings: HashMap::new() } }
+
+    /**
+     * 从配置文件加载，每行的格式为 `动作 = 按键, 按键`，以 # 开头的行为注释
+     * 文件中出现的动作会覆盖已有的绑定，同一动作出现多次时以最后一行为准
+     * 出错时不修改已有的绑定
+     */
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), InputError> {
+        let source = fs::read_to_string(path)?;
+        self.parse(&source)
+    }
+
+    pub fn parse(&mut self, source: &str) -> Result<(), InputError> {
+        let mut parsed = HashMap::new();
+        for (i, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+
+            let (action, inputs) = line.split_once('=').ok_or(InputError::SyntaxError(i + 1, line.into()))?;
+
+            let mut bindings = Vec::new();
+            for name in inputs.split(',').filter(|n| !n.trim().is_empty()) {
+                bindings.push(Binding::parse(name).ok_or_else(|| InputError::UnknownBinding(name.trim().into()))?);
+            }
+
+            parsed.insert(action.trim().to_string(), bindings);
+        }
+
+        self.bindings.extend(parsed);
+        Ok(())
+    }
+
+    pub fn bind<T: Into<String>>(&mut self, action: T, binding: Binding) {
+        let bindings = self.bindings.entry(action.into()).or_default();
+        if !bindings.contains(&binding) { bindings.push(binding); }
+    }
+
+    // 清除动作原有的绑定
+    pub fn rebind<T: Into<String>>(&mut self, action: T, bindings: &[Binding]) {
+        self.bindings.insert(action.into(), bindings.to_vec());
+    }
+
+    pub fn unbind(&mut self, action: &str) { self.bindings.remove(action); }
+
+    pub fn get(&self, action: &str) -> &[Binding] {
+        self.bindings.get(action).map_or(&[], |b| b.as_slice())
+    }
+}
+
+/**
+ * 当前帧的输入状态，由引擎根据窗口事件更新
+ */
+#[derive(Debug, Default)]
+pub struct InputState {
+    keys_down: HashSet<Key>,
+    buttons_down: HashSet<MouseButton>,
+    pressed: HashSet<Binding>,              // 本帧内被按下的输入
+    released: HashSet<Binding>,             // 本帧内被松开的输入
+
+    cursor_pos: (f32, f32),                 // 鼠标的位置
+    has_cursor_pos: bool,                   // 是否已经收到过鼠标位置，第一次移动不计算增量
+    cursor_delta: (f32, f32),               // 本帧鼠标移动的距离
+    scroll_delta: (f32, f32),               // 本帧滚轮滚动的距离
+    chars: Vec<char>,                       // 本帧输入的字符
+
+    actions: ActionMap,
+}
+
+impl InputState {
+    pub fn new(actions: ActionMap) -> Self {
+        Self { actions, ..Default::default() }
+    }
+
+    pub fn actions(&self) -> &ActionMap { &self.actions }
+
+    pub fn actions_mut(&mut self) -> &mut ActionMap { &mut self.actions }
+
+    pub fn is_key_down(&self, key: Key) -> bool { self.keys_down.contains(&key) }
+
+    pub fn is_mouse_down(&self, button: MouseButton) -> bool { self.buttons_down.contains(&button) }
+
+    pub fn is_down(&self, binding: Binding) -> bool {
+        match binding {
+            Binding::Key(key) => self.is_key_down(key),
+            Binding::Mouse(button) => self.is_mouse_down(button),
+        }
+    }
+
+    // 本帧内刚被按下，按住不放时只在第一帧返回 true
+    pub fn is_pressed(&self, binding: Binding) -> bool { self.pressed.contains(&binding) }
+
+    // 本帧内刚被松开
+    pub fn is_released(&self, binding: Binding) -> bool { self.released.contains(&binding) }
+
+    // 动作绑定的任意一个输入处于按下状态
+    pub fn is_action_down(&self, action: &str) -> bool {
+        self.actions.get(action).iter().any(|b| self.is_down(*b))
+    }
+
+    // 动作绑定的任意一个输入本帧内刚被按下，用于只触发一次的动作
+    pub fn is_action_pressed(&self, action: &str) -> bool {
+        self.actions.get(action).iter().any(|b| self.is_pressed(*b))
+    }
+
+    // 动作绑定的任意一个输入本帧内刚被松开
+    pub fn is_action_released(&self, action: &str) -> bool {
+        self.actions.get(action).iter().any(|b| self.is_released(*b))
+    }
+
+    pub fn cursor_pos(&self) -> (f32, f32) { self.cursor_pos }
+
+    pub fn cursor_delta(&self) -> (f32, f32) { self.cursor_delta }
+
+    pub fn scroll_delta(&self) -> (f32, f32) { self.scroll_delta }
+
+    pub fn chars(&self) -> &[char] { &self.chars }
+
+    // 新的一帧开始，清除上一帧累计的增量
+    pub(crate) fn begin_frame(&mut self) {
+        self.cursor_delta = (0.0, 0.0);
+        self.scroll_delta = (0.0, 0.0);
+        self.chars.clear();
+        self.pressed.clear();
+        self.released.clear();
+    }
+
+    pub(crate) fn handle_event(&mut self, event: &WindowEvent) {
+        match *event {
+            WindowEvent::Key(key, _, Action::Press, _) => {
+                self.keys_down.insert(key);
+                self.pressed.insert(Binding::Key(key));
+            },
+            WindowEvent::Key(key, _, Action::Release, _) => {
+                self.keys_down.remove(&key);
+                self.released.insert(Binding::Key(key));
+            },
+            WindowEvent::MouseButton(button, Action::Press, _) => {
+                self.buttons_down.insert(button);
+                self.pressed.insert(Binding::Mouse(button));
+            },
+            WindowEvent::MouseButton(button, Action::Release, _) => {
+                self.buttons_down.remove(&button);
+                self.released.insert(Binding::Mouse(button));
+            },
+            WindowEvent::CursorPos(x, y) => {
+                let (x, y) = (x as f32, y as f32);
+                if self.has_cursor_pos {
+                    self.cursor_delta.0 += x - self.cursor_pos.0;
+                    self.cursor_delta.1 += y - self.cursor_pos.1;
+                }
+                self.cursor_pos = (x, y);
+                self.has_cursor_pos = true;
+            },
+            WindowEvent::Scroll(x, y) => {
+                self.scroll_delta.0 += x as f32;
+                self.scroll_delta.1 += y as f32;
+            },
+            WindowEvent::Char(c) => self.chars.push(c),
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glfw::Modifiers;
+
+    use super::*;
+
+    fn key_event(key: Key, action: Action) -> WindowEvent { WindowEvent::Key(key, 0, action, Modifiers::empty()) }
+
+    #[test]
+    fn parse_skips_comments_and_blank_lines() {
+        let mut map = ActionMap::empty();
+        map.parse("# 移动\n\n   \njump = Space, W\n  # 缩进的注释\nfire = mouseleft\n").unwrap();
+
+        assert_eq!(map.get("jump"), &[Binding::Key(Key::Space), Binding::Key(Key::W)]);
+        assert_eq!(map.get("fire"), &[Binding::Mouse(MouseButton::Button1)]);
+        assert_eq!(map.bindings.len(), 2);
+    }
+
+    #[test]
+    fn parse_overrides_existing_bindings() {
+        let mut map = ActionMap::default();
+        map.parse("move_forward = Up").unwrap();
+
+        assert_eq!(map.get(MOVE_FORWARD), &[Binding::Key(Key::Up)]);
+        assert_eq!(map.get(MOVE_BACKWARD), &[Binding::Key(Key::S)]);
+    }
+
+    #[test]
+    fn parse_duplicate_action_keeps_last_line() {
+        let mut map = ActionMap::empty();
+        map.parse("jump = Space\njump = J, K").unwrap();
+
+        assert_eq!(map.get("jump"), &[Binding::Key(Key::J), Binding::Key(Key::K)]);
+    }
+
+    #[test]
+    fn parse_empty_binding_list_unbinds() {
+        let mut map = ActionMap::default();
+        map.parse("quit =").unwrap();
+
+        assert!(map.get(QUIT).is_empty());
+    }
+
+    #[test]
+    fn parse_rejects_unknown_key() {
+        let mut map = ActionMap::empty();
+        let err = map.parse("jump = Space\nfire = Banana").unwrap_err();
+
+        assert!(matches!(err, InputError::UnknownBinding(name) if name == "Banana"));
+        // 出错时不应用任何一行
+        assert!(map.get("jump").is_empty());
+    }
+
+    #[test]
+    fn parse_reports_line_of_syntax_error() {
+        let mut map = ActionMap::empty();
+        let err = map.parse("# 注释\n\njump Space").unwrap_err();
+
+        assert!(matches!(err, InputError::SyntaxError(3, line) if line == "jump Space"));
+    }
+
+    #[test]
+    fn pressed_and_released_last_one_frame() {
+        let mut input = InputState::default();
+
+        input.begin_frame();
+        input.handle_event(&key_event(Key::W, Action::Press));
+        assert!(input.is_action_pressed(MOVE_FORWARD));
+        assert!(input.is_action_down(MOVE_FORWARD));
+
+        // 按住不放时只有第一帧算作按下，系统的重复事件也不算
+        input.begin_frame();
+        input.handle_event(&key_event(Key::W, Action::Repeat));
+        assert!(!input.is_action_pressed(MOVE_FORWARD));
+        assert!(input.is_action_down(MOVE_FORWARD));
+
+        input.begin_frame();
+        input.handle_event(&key_event(Key::W, Action::Release));
+        assert!(input.is_action_released(MOVE_FORWARD));
+        assert!(!input.is_action_down(MOVE_FORWARD));
+
+        input.begin_frame();
+        assert!(!input.is_action_released(MOVE_FORWARD));
+    }
+
+    #[test]
+    fn press_and_release_in_one_frame_are_both_seen() {
+        let mut input = InputState::default();
+
+        input.begin_frame();
+        input.handle_event(&key_event(Key::F12, Action::Press));
+        input.handle_event(&key_event(Key::F12, Action::Release));
+
+        assert!(input.is_action_pressed(SCREENSHOT));
+        assert!(input.is_action_released(SCREENSHOT));
+        assert!(!input.is_action_down(SCREENSHOT));
+    }
+
+    #[test]
+    fn mouse_buttons_trigger_actions() {
+        let mut input = InputState::new(ActionMap::empty());
+        input.actions_mut().bind("fire", Binding::Mouse(MouseButton::Button1));
+
+        input.begin_frame();
+        input.handle_event(&WindowEvent::MouseButton(MouseButton::Button1, Action::Press, Modifiers::empty()));
+
+        assert!(input.is_action_pressed("fire"));
+        assert!(input.is_mouse_down(MouseButton::Button1));
+    }
+}
@@ -10,6 +10,9 @@ use crate::base::program::ShaderProgram;
 use crate::base::buffer::Buffer;
 use crate::base::texture::Texture;
 use crate::base::vertex_array::VertexArray;
+use crate::base::frustum::Aabb;
+use crate::base::ray::Ray;
+use crate::base::shader_variant::{ShaderFeatures, ShaderVariants};
 
 #[repr(C, packed)]
 pub struct MeshVertex {
@@ -40,6 +43,7 @@ pub struct Mesh {
     pub indices: Vec<u32>,
     pub textures: Vec<MeshTexture>,
     pub vao: VertexArray,
+    pub aabb: Aabb,                             // 模型空间的包围盒
 
     vbo: Buffer,
     ebo: Buffer,
@@ -47,6 +51,7 @@ pub struct Mesh {
 
 impl Mesh {
     pub unsafe fn new(vertices: Vec<MeshVertex>, indices: Vec<u32>, textures: Vec<MeshTexture>) -> Self {
+        let aabb = Aabb::from_points(vertices.iter().map(|v| v.position)).unwrap_or(Aabb::new(glm::Vec3::zeros(), glm::Vec3::zeros()));
 
         let vao = VertexArray::new();
         let vbo = Buffer::new(gl::ARRAY_BUFFER, vertices.as_slice(), gl::STATIC_DRAW);
@@ -57,7 +62,19 @@ impl Mesh {
         set_attribute!(vao, 2, MeshVertex::texCoords);
         vao.unbind();
 
-        Mesh { vertices, indices, textures, vao, vbo, ebo }
+        Mesh { vertices, indices, textures, vao, aabb, vbo, ebo }
+    }
+
+    // 与射线最近的三角形，返回 (三角形下标, 距离)，射线需要在模型空间中
+    pub fn raycast(&self, ray: &Ray) -> Option<(usize, f32)> {
+        ray.intersect_aabb(&self.aabb)?;
+
+        self.indices.chunks_exact(3).enumerate()
+            .filter_map(|(i, tri)| {
+                let [a, b, c] = [0, 1, 2].map(|k| self.vertices[tri[k] as usize].position);
+                ray.intersect_triangle(&a, &b, &c).map(|t| (i, t))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
     }
 
     pub unsafe fn draw(&self, program: &ShaderProgram) -> Result<(), GLError> {
@@ -95,11 +112,30 @@ impl Mesh {
             gl::BindTexture(gl::TEXTURE_2D, texture.tex.id());
         }
 
-        self.vao.bind();
-        gl::DrawElements(gl::TRIANGLES, self.indices.len() as i32, gl::UNSIGNED_INT, ptr::null());
-        self.vao.unbind();
+        self.draw_geometry();
         gl::ActiveTexture(gl::TEXTURE0);        
 
         Ok(())
     }
+
+    // 网格拥有的纹理对应的着色器特性
+    pub fn features(&self) -> ShaderFeatures { ShaderFeatures::from_textures(&self.textures) }
+
+    /**
+     * 根据网格拥有的纹理选择着色器变体并绘制
+     * set_uniforms: 为选中的程序设置纹理以外的 uniform 变量
+     */
+    pub unsafe fn draw_variant<F>(&self, variants: &ShaderVariants, set_uniforms: F) -> Result<(), GLError>
+    where F: Fn(&ShaderProgram) -> Result<(), GLError> {
+        let program = variants.get(self.features())?;
+        set_uniforms(&program)?;
+        self.draw(&program)
+    }
+
+    // 只提交几何数据，不绑定纹理，由调用者设置好着色器
+    pub unsafe fn draw_geometry(&self) {
+        self.vao.bind();
+        gl::DrawElements(gl::TRIANGLES, self.indices.len() as i32, gl::UNSIGNED_INT, ptr::null());
+        self.vao.unbind();
+    }
 }
\ No newline at end of file
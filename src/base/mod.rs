@@ -1,11 +1,27 @@
 pub mod engine;
 pub mod camera;
+pub mod camera_path;
+pub mod input;
 pub mod error;
 pub mod mesh;
 pub mod model;
 pub mod program;
+pub mod ray;
 pub mod buffer;
+pub mod compute;
+pub mod framebuffer;
+pub mod frustum;
+pub mod picking;
+pub mod post_process;
+pub mod preprocessor;
 pub mod texture;
+pub mod uniform;
+pub mod uniform_buffer;
 pub mod utility;
 pub mod shader;
-pub mod vertex_array;
\ No newline at end of file
+pub mod shader_source;
+pub mod shader_variant;
+pub mod stats;
+pub mod std140;
+pub mod vertex_array;
+pub mod golden;
\ No newline at end of file
@@ -4,7 +4,7 @@
 use std::path::Path;
 use nalgebra_glm as glm;
 
-use crate::base::{mesh::{Mesh, MeshTexture, MeshVertex}, error::{ModelError, GLError}, texture::Texture, program::ShaderProgram};
+use crate::base::{mesh::{Mesh, MeshTexture, MeshVertex}, error::{ModelError, GLError}, texture::Texture, program::ShaderProgram, frustum::{Aabb, Frustum}, ray::{Ray, RayHit}, shader_variant::ShaderVariants};
 
 #[derive(Debug, PartialEq)]
 pub enum MaterialType {
@@ -31,13 +31,74 @@ impl Model {
         Ok(model)
     }
 
+    // 绘制所有网格，不做剔除
     pub fn draw(&self, program: &ShaderProgram) -> Result<(), GLError> {
-        for mesh in &self.meshes {
+        self.draw_meshes(program, None)
+    }
+
+    /**
+     * 只绘制与视锥体相交的网格
+     * frustum: 世界空间的视锥体，如 Camera::get_frustum 的结果
+     * model_matrix: 绘制该模型时使用的模型矩阵，需要与着色器中的一致
+     */
+    pub fn draw_culled(&self, program: &ShaderProgram, frustum: &Frustum, model_matrix: &glm::Mat4) -> Result<(), GLError> {
+        self.draw_meshes(program, Some((frustum, model_matrix)))
+    }
+
+    /**
+     * 每个网格使用与其纹理对应的着色器变体绘制，不做剔除
+     * set_uniforms: 为选中的程序设置纹理以外的 uniform 变量
+     */
+    pub fn draw_variants<F>(&self, variants: &ShaderVariants, set_uniforms: F) -> Result<(), GLError>
+    where F: Fn(&ShaderProgram) -> Result<(), GLError> {
+        self.draw_meshes_variants(variants, None, set_uniforms)
+    }
+
+    // 同 draw_variants，只绘制与视锥体相交的网格，参数同 draw_culled
+    pub fn draw_variants_culled<F>(&self, variants: &ShaderVariants, frustum: &Frustum, model_matrix: &glm::Mat4, set_uniforms: F) -> Result<(), GLError>
+    where F: Fn(&ShaderProgram) -> Result<(), GLError> {
+        self.draw_meshes_variants(variants, Some((frustum, model_matrix)), set_uniforms)
+    }
+
+    fn draw_meshes(&self, program: &ShaderProgram, culling: Option<(&Frustum, &glm::Mat4)>) -> Result<(), GLError> {
+        for mesh in self.visible_meshes(culling) {
             unsafe { mesh.draw(program)?; }
         }
         Ok(())
     }
 
+    fn draw_meshes_variants<F>(&self, variants: &ShaderVariants, culling: Option<(&Frustum, &glm::Mat4)>, set_uniforms: F) -> Result<(), GLError>
+    where F: Fn(&ShaderProgram) -> Result<(), GLError> {
+        for mesh in self.visible_meshes(culling) {
+            unsafe { mesh.draw_variant(variants, &set_uniforms)?; }
+        }
+        Ok(())
+    }
+
+    // 包围盒变换到世界空间后与视锥体比较
+    fn visible_meshes<'a>(&'a self, culling: Option<(&'a Frustum, &'a glm::Mat4)>) -> impl Iterator<Item = &'a Mesh> {
+        self.meshes.iter().filter(move |mesh| culling.is_none_or(|(frustum, model_matrix)| frustum.intersects_aabb(&mesh.aabb.transform(model_matrix))))
+    }
+
+    // 模型空间的包围盒
+    pub fn aabb(&self) -> Option<Aabb> {
+        self.meshes.iter().map(|m| m.aabb).reduce(|a, b| a.union(&b))
+    }
+
+    /**
+     * 射线拾取，返回离射线起点最近的交点
+     * ray: 世界空间的射线
+     * model_matrix: 绘制该模型时使用的模型矩阵
+     */
+    pub fn raycast(&self, ray: &Ray, model_matrix: &glm::Mat4) -> Option<RayHit> {
+        let local = ray.transform(&glm::inverse(model_matrix));
+
+        self.meshes.iter().enumerate()
+            .filter_map(|(i, mesh)| mesh.raycast(&local).map(|(triangle, t)| (i, triangle, t)))
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(mesh, triangle, t)| RayHit { mesh, triangle, point: ray.at(t), distance: t })
+    }
+
     fn loadModel(&mut self, path: &str, load_field: Option<&[MaterialType]>) -> Result<(), ModelError> {
         let path = Path::new(path);
 
@@ -0,0 +1,135 @@
+#![allow(dead_code)]
+
+use std::cell::Cell;
+
+use nalgebra_glm as glm;
+
+use crate::base::camera::Camera;
+use crate::base::error::GLError;
+use crate::base::framebuffer::{ColorFormat, DepthStencil, Framebuffer};
+use crate::base::frustum::Frustum;
+use crate::base::model::Model;
+use crate::base::mesh::Mesh;
+use crate::base::program::ShaderProgram;
+
+const PICKING_VS: &str = "glsl/picking/picking.vs";
+const PICKING_FS: &str = "glsl/picking/picking.fs";
+
+/**
+ * 拾取结果
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PickResult {
+    pub object: u32,                            // 调用 draw_model / draw_mesh 时传入的物体 ID
+    pub mesh: usize,                            // Model::meshes 中的下标
+}
+
+/**
+ * GPU 拾取：把每个网格以唯一的 ID 绘制到整数帧缓冲中，再读取光标下的像素
+ *
+ * 用法：
+ * pass.begin(&camera);
+ * pass.draw_model(&model, &model_matrix, 1)?;
+ * pass.end();
+ * let hit = pass.read(cursor);
+ */
+pub struct PickingPass {
+    framebuffer: Framebuffer,                   // RG32UI 颜色附件 + 深度附件
+    program: ShaderProgram,
+    frustum: Cell<Option<Frustum>>,             // begin 时摄像机的视锥体
+}
+
+impl PickingPass {
+    pub unsafe fn new(size: (u32, u32)) -> Result<Self, GLError> {
+        let framebuffer = Framebuffer::new(size, &[ColorFormat::RG32UI], DepthStencil::Depth)?;
+        let program = ShaderProgram::new(PICKING_VS, PICKING_FS)?;
+
+        Ok(Self { framebuffer, program, frustum: Cell::new(None) })
+    }
+
+    // 需要与窗口的帧缓冲大小保持一致
+    pub unsafe fn resize(&mut self, size: (u32, u32)) -> Result<(), GLError> {
+        if self.framebuffer.size() == size { return Ok(()); }
+        self.framebuffer.resize(size)
+    }
+
+    pub fn size(&self) -> (u32, u32) { self.framebuffer.size() }
+
+    /**
+     * 绑定拾取帧缓冲并清空，之后的 draw_model / draw_mesh 会写入 ID
+     * 会修改视口和当前使用的着色器程序
+     */
+    pub unsafe fn begin(&self, camera: &Camera) -> Result<(), GLError> {
+        self.framebuffer.bind();
+
+        let clear = [0u32; 4];
+        gl::ClearBufferuiv(gl::COLOR, 0, clear.as_ptr());
+        gl::Clear(gl::DEPTH_BUFFER_BIT);
+        gl::Enable(gl::DEPTH_TEST);
+
+        self.program.set_mat4("projection", glm::value_ptr(&camera.get_projection_matrix()))?;
+        self.program.set_mat4("view", glm::value_ptr(&camera.get_view_matrix()))?;
+        self.frustum.set(Some(camera.get_frustum()));
+
+        Ok(())
+    }
+
+    // object: 物体 ID，不能为 0；视锥体外的网格不会绘制
+    pub unsafe fn draw_model(&self, model: &Model, model_matrix: &glm::Mat4, object: u32) -> Result<(), GLError> {
+        self.set_object(model_matrix, object)?;
+
+        let frustum = self.frustum.get();
+        for (i, mesh) in model.meshes.iter().enumerate() {
+            if frustum.is_some_and(|frustum| !frustum.intersects_aabb(&mesh.aabb.transform(model_matrix))) { continue; }
+
+            self.program.set_uint("meshID", i as u32 + 1)?;
+            mesh.draw_geometry();
+        }
+
+        Ok(())
+    }
+
+    // 单独绘制一个网格，拾取结果中的 mesh 为 0
+    pub unsafe fn draw_mesh(&self, mesh: &Mesh, model_matrix: &glm::Mat4, object: u32) -> Result<(), GLError> {
+        self.set_object(model_matrix, object)?;
+        self.program.set_uint("meshID", 1)?;
+        mesh.draw_geometry();
+
+        Ok(())
+    }
+
+    // 恢复默认帧缓冲，视口需要由调用者恢复
+    pub unsafe fn end(&self) { self.framebuffer.unbind(); }
+
+    /**
+     * 读取像素对应的 ID，没有物体时返回 None
+     * pixel: 帧缓冲像素坐标，左上角为原点；高 DPI 屏幕下需要先把光标坐标乘以缩放比例
+     */
+    pub unsafe fn read(&self, pixel: (f64, f64)) -> Option<PickResult> {
+        let (width, height) = self.framebuffer.size();
+        let x = pixel.0.floor() as i64;
+        let y = height as i64 - 1 - pixel.1.floor() as i64;
+        if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 { return None; }
+
+        let mut value = [0u32; 2];
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.framebuffer.id);
+        gl::ReadBuffer(gl::COLOR_ATTACHMENT0);
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl::ReadPixels(x as i32, y as i32, 1, 1, gl::RG_INTEGER, gl::UNSIGNED_INT, value.as_mut_ptr().cast());
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+
+        match value {
+            [0, _] | [_, 0] => None,
+            [object, mesh] => Some(PickResult { object, mesh: mesh as usize - 1 }),
+        }
+    }
+
+    unsafe fn set_object(&self, model_matrix: &glm::Mat4, object: u32) -> Result<(), GLError> {
+        debug_assert!(object != 0, "物体 ID 0 表示没有物体");
+
+        self.program.set_mat4("model", glm::value_ptr(model_matrix))?;
+        self.program.set_uint("objectID", object)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+#![allow(dead_code)]
+
+use gl::types::GLint;
+
+use crate::base::{
+    buffer::Buffer, 
+    error::{GLError, ShaderError}, 
+    framebuffer::{ColorFormat, DepthStencil, Framebuffer}, 
+    program::ShaderProgram, 
+    vertex_array::VertexArray
+};
+use crate::set_attribute;
+
+const QUAD_VERTEX_SOURCE_FILE: &str = "glsl/post_process/quad.vs";
+const GRAYSCALE_FRAGMENT_SOURCE_FILE: &str = "glsl/post_process/grayscale.fs";
+const INVERSION_FRAGMENT_SOURCE_FILE: &str = "glsl/post_process/inversion.fs";
+const KERNEL_FRAGMENT_SOURCE_FILE: &str = "glsl/post_process/kernel.fs";
+const GAMMA_FRAGMENT_SOURCE_FILE: &str = "glsl/post_process/gamma.fs";
+const TONE_MAPPING_FRAGMENT_SOURCE_FILE: &str = "glsl/post_process/tone_mapping.fs";
+
+// 卷积核的采样间隔
+const KERNEL_OFFSET: f32 = 1.0 / 300.0;
+
+const SHARPEN_KERNEL: [f32; 9] = [
+    -1.0, -1.0, -1.0,
+    -1.0,  9.0, -1.0,
+    -1.0, -1.0, -1.0,
+];
+
+const BLUR_KERNEL: [f32; 9] = [
+    1.0 / 16.0, 2.0 / 16.0, 1.0 / 16.0,
+    2.0 / 16.0, 4.0 / 16.0, 2.0 / 16.0,
+    1.0 / 16.0, 2.0 / 16.0, 1.0 / 16.0,
+];
+
+type Pos = [f32; 2];
+type TextureCoords = [f32; 2];
+
+#[repr(C, packed)]
+struct QuadVertex(Pos, TextureCoords);
+
+// 覆盖整个屏幕的四边形，以两个三角形绘制
+const QUAD_VERTICES: [QuadVertex; 6] = [
+    QuadVertex([-1.0,  1.0], [0.0, 1.0]),
+    QuadVertex([-1.0, -1.0], [0.0, 0.0]),
+    QuadVertex([ 1.0, -1.0], [1.0, 0.0]),
+    QuadVertex([-1.0,  1.0], [0.0, 1.0]),
+    QuadVertex([ 1.0, -1.0], [1.0, 0.0]),
+    QuadVertex([ 1.0,  1.0], [1.0, 1.0]),
+];
+
+/**
+ * 后处理效果：一个对屏幕纹理采样的着色器程序，以及它的 float 类型 uniform 参数
+ */
+pub struct PostEffect {
+    name: String,
+    program: ShaderProgram,
+    params: Vec<(String, f32)>,
+    enabled: bool,
+}
+
+impl PostEffect {
+    pub unsafe fn new<T: Into<String>>(name: T, file_fs: &str) -> Result<Self, GLError> {
+        let program = ShaderProgram::new(QUAD_VERTEX_SOURCE_FILE, file_fs)?;
+        program.set_int("screenTexture", 0)?;
+
+        Ok(Self { name: name.into(), program, params: Vec::new(), enabled: true })
+    }
+
+    pub unsafe fn grayscale() -> Result<Self, GLError> { Self::new("grayscale", GRAYSCALE_FRAGMENT_SOURCE_FILE) }
+
+    pub unsafe fn inversion() -> Result<Self, GLError> { Self::new("inversion", INVERSION_FRAGMENT_SOURCE_FILE) }
+
+    pub unsafe fn sharpen() -> Result<Self, GLError> { Self::kernel("sharpen", &SHARPEN_KERNEL) }
+
+    pub unsafe fn blur() -> Result<Self, GLError> { Self::kernel("blur", &BLUR_KERNEL) }
+
+    // 3x3 卷积核，按行排列
+    pub unsafe fn kernel<T: Into<String>>(name: T, kernel: &[f32; 9]) -> Result<Self, GLError> {
+        let mut ret = Self::new(name, KERNEL_FRAGMENT_SOURCE_FILE)?;
+        ret.set_param("offset", KERNEL_OFFSET);
+        for (i, value) in kernel.iter().enumerate() {
+            ret.set_param(&format!("kernel[{}]", i), *value);
+        }
+        Ok(ret)
+    }
+
+    pub unsafe fn gamma_correction(gamma: f32) -> Result<Self, GLError> {
+        let mut ret = Self::new("gamma", GAMMA_FRAGMENT_SOURCE_FILE)?;
+        ret.set_param("gamma", gamma);
+        Ok(ret)
+    }
+
+    pub unsafe fn tone_mapping(exposure: f32) -> Result<Self, GLError> {
+        let mut ret = Self::new("tone_mapping", TONE_MAPPING_FRAGMENT_SOURCE_FILE)?;
+        ret.set_param("exposure", exposure);
+        Ok(ret)
+    }
+
+    pub fn name(&self) -> &str { &self.name }
+
+    pub fn program(&self) -> &ShaderProgram { &self.program }
+
+    pub fn is_enabled(&self) -> bool { self.enabled }
+
+    pub fn set_enabled(&mut self, enabled: bool) { self.enabled = enabled; }
+
+    // 设置 uniform 参数，在每次绘制前上传
+    pub fn set_param(&mut self, name: &str, value: f32) {
+        match self.params.iter_mut().find(|(n, _)| n == name) {
+            Some(param) => param.1 = value,
+            None => self.params.push((name.into(), value)),
+        }
+    }
+
+    unsafe fn apply(&self) -> Result<(), ShaderError> {
+        self.program.apply();
+        for (name, value) in &self.params {
+            self.program.set_float(name, *value)?;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * 后处理链：场景先绘制到离屏帧缓冲，再依次经过启用的效果，最后一个效果输出到默认帧缓冲
+ */
+pub struct PostProcess {
+    scene: Framebuffer,                     // 场景的渲染目标，使用浮点格式以保留 HDR 颜色
+    ping_pong: [Framebuffer; 2],            // 效果之间交替读写的中间结果
+    effects: Vec<PostEffect>,
+
+    quad_buffer: Buffer,
+    quad_array: VertexArray,
+    present: PostEffect,                    // 没有启用任何效果时，直接输出场景
+}
+
+impl PostProcess {
+    pub unsafe fn new(size: (u32, u32)) -> Result<Self, GLError> {
+        let scene = Framebuffer::new(size, &[ColorFormat::RGBA16F], DepthStencil::DepthStencil)?;
+        let ping_pong = [
+            Framebuffer::new(size, &[ColorFormat::RGBA16F], DepthStencil::None)?,
+            Framebuffer::new(size, &[ColorFormat::RGBA16F], DepthStencil::None)?,
+        ];
+
+        let quad_array = VertexArray::new();
+        let quad_buffer = Buffer::new(gl::ARRAY_BUFFER, &QUAD_VERTICES, gl::STATIC_DRAW);
+        set_attribute!(quad_array, 0, QuadVertex::0);
+        set_attribute!(quad_array, 1, QuadVertex::1);
+        quad_array.unbind();
+
+        let present = PostEffect::kernel("present", &[0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0])?;
+
+        Ok(Self { scene, ping_pong, effects: Vec::new(), quad_buffer, quad_array, present })
+    }
+
+    pub fn push(&mut self, effect: PostEffect) { self.effects.push(effect); }
+
+    pub fn effects(&self) -> &[PostEffect] { &self.effects }
+
+    pub fn effect_mut(&mut self, name: &str) -> Option<&mut PostEffect> {
+        self.effects.iter_mut().find(|e| e.name == name)
+    }
+
+    // 切换第 index 个效果的启用状态
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(effect) = self.effects.get_mut(index) {
+            effect.enabled = !effect.enabled;
+        }
+    }
+
+    // 重新加载源文件有修改的着色器
+    pub unsafe fn reload_shaders(&mut self) {
+        for effect in &mut self.effects {
+            effect.program.reload_if_changed();
+        }
+        self.present.program.reload_if_changed();
+    }
+
+    pub unsafe fn resize(&mut self, size: (u32, u32)) -> Result<(), GLError> {
+        self.scene.resize(size)?;
+        for fb in &mut self.ping_pong {
+            fb.resize(size)?;
+        }
+        Ok(())
+    }
+
+    // 绑定场景帧缓冲，之后的绘制都会进入后处理链
+    pub unsafe fn begin(&self) { self.scene.bind(); }
+
+    // 依次执行启用的效果，结果输出到 target，为 None 时输出到默认帧缓冲
+    pub unsafe fn end(&self, target: Option<&Framebuffer>, target_size: (u32, u32)) -> Result<(), GLError> {
+        let is_depth_test = gl::IsEnabled(gl::DEPTH_TEST) == gl::TRUE;
+        gl::Disable(gl::DEPTH_TEST);
+        self.quad_array.bind();
+
+        let enabled: Vec<&PostEffect> = self.effects.iter().filter(|e| e.enabled).collect();
+        let mut source = &self.scene;
+
+        if enabled.is_empty() {
+            self.present.apply()?;
+            self.draw_quad(source, target, target_size);
+        }
+
+        for (i, effect) in enabled.iter().enumerate() {
+            effect.apply()?;
+
+            if i + 1 == enabled.len() {
+                self.draw_quad(source, target, target_size);
+            } else {
+                let next = &self.ping_pong[i % 2];
+                self.draw_quad(source, Some(next), next.size());
+                source = next;
+            }
+        }
+
+        self.quad_array.unbind();
+        if is_depth_test { gl::Enable(gl::DEPTH_TEST); }
+
+        Ok(())
+    }
+
+    unsafe fn draw_quad(&self, source: &Framebuffer, target: Option<&Framebuffer>, target_size: (u32, u32)) {
+        gl::BindFramebuffer(gl::FRAMEBUFFER, target.map_or(0, |fb| fb.id));
+        gl::Viewport(0, 0, target_size.0 as GLint, target_size.1 as GLint);
+
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+        source.activate_color(0, gl::TEXTURE0);
+        gl::DrawArrays(gl::TRIANGLES, 0, 6);
+    }
+}
@@ -0,0 +1,327 @@
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use crate::base::{error::ShaderError, shader_source};
+
+/**
+ * 着色器预处理器
+ * 1. 展开 #include "file"，路径相对于当前文件所在的文件夹，同一个文件（按规范化后的路径比较）只会被包含一次；文件通过 shader_source 读取
+ * 2. 在 #version 之后注入由 Rust 传入的 #define
+ * 3. 插入 #line 指令，编译日志中的行号会被映射回原始的文件与行
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Preprocessor {
+    defines: Vec<(String, String)>,
+}
+
+/**
+ * 预处理的结果
+ */
+#[derive(Debug, Clone)]
+pub struct ProcessedSource {
+    pub code: String,
+    pub files: Vec<PathBuf>,                    // 源字符串编号对应的文件，0 为入口文件
+}
+
+impl Preprocessor {
+    pub fn new() -> Self { Self::default() }
+
+    // 添加宏定义，value 为空时只定义名字
+    pub fn define<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.set_define(name, value);
+        self
+    }
+
+    pub fn set_define<N: Into<String>, V: Into<String>>(&mut self, name: N, value: V) {
+        let name = name.into();
+        let value = value.into();
+        match self.defines.iter_mut().find(|(n, _)| *n == name) {
+            Some(define) => define.1 = value,
+            None => self.defines.push((name, value)),
+        }
+    }
+
+    pub fn defines(&self) -> &[(String, String)] { &self.defines }
+
+    pub fn process<P: AsRef<Path>>(&self, path: P) -> Result<ProcessedSource, ShaderError> {
+        let path = path.as_ref();
+        let source = shader_source::read(path)?;
+
+        self.process_source(&source, path)
+    }
+
+    /**
+     * 处理内存中的源码
+     * path: 源码对应的路径，用于解析 #include 和在错误日志中显示
+     */
+    pub fn process_source<P: AsRef<Path>>(&self, source: &str, path: P) -> Result<ProcessedSource, ShaderError> {
+        let path = shader_source::normalize(path.as_ref());
+        let mut ret = ProcessedSource { code: String::with_capacity(source.len()), files: vec![path] };
+
+        let mut lines = source.lines().enumerate().peekable();
+
+        // #version 必须是第一条语句，宏定义放在它后面；跳过它前面的空行与注释
+        let mut first_line = 1;
+        let mut in_comment = false;
+        while let Some((_, line)) = lines.peek() {
+            let rest = Self::skip_comments(line, &mut in_comment);
+            if rest.is_empty() {
+                lines.next();
+                continue;
+            }
+            if rest.starts_with("#version") {
+                ret.code.push_str(rest);
+                ret.code.push('\n');
+                lines.next();
+            }
+            break;
+        }
+        if let Some((n, _)) = lines.peek() { first_line = n + 1; }
+
+        for (name, value) in &self.defines {
+            ret.code.push_str(&format!("#define {} {}\n", name, value));
+        }
+        ret.code.push_str(&format!("#line {} 0\n", first_line));
+
+        let rest: Vec<(usize, &str)> = lines.collect();
+        Self::expand(&mut ret, &rest, 0)?;
+
+        Ok(ret)
+    }
+
+    // 逐行展开 #include，lines 为 (行下标, 内容)
+    fn expand(ret: &mut ProcessedSource, lines: &[(usize, &str)], file_index: usize) -> Result<(), ShaderError> {
+        for &(n, line) in lines {
+            let directive = line.trim_start();
+            if !directive.starts_with("#include") {
+                ret.code.push_str(line);
+                ret.code.push('\n');
+                continue;
+            }
+
+            let current = &ret.files[file_index];
+            let name = Self::parse_include(directive).ok_or_else(|| {
+                ShaderError::PreprocessError(format!("{}:{}: malformed #include", current.display(), n + 1))
+            })?;
+            let include = shader_source::normalize(&current.parent().unwrap_or(Path::new("")).join(name));
+
+            // 已经包含过的文件直接跳过，保留空行以免影响行号
+            if ret.files.contains(&include) {
+                ret.code.push('\n');
+                continue;
+            }
+
+            let source = shader_source::read(&include).map_err(|err| {
+                ShaderError::PreprocessError(format!("{}:{}: cannot open \"{}\": {}", current.display(), n + 1, include.display(), err))
+            })?;
+            ret.files.push(include);
+            let index = ret.files.len() - 1;
+
+            // 被包含的文件中的 #version 会被忽略
+            let included: Vec<(usize, &str)> = source.lines().enumerate()
+                .map(|(i, l)| if l.trim_start().starts_with("#version") { (i, "") } else { (i, l) })
+                .collect();
+
+            ret.code.push_str(&format!("#line 1 {}\n", index));
+            Self::expand(ret, &included, index)?;
+            ret.code.push_str(&format!("#line {} {}\n", n + 2, file_index));
+        }
+
+        Ok(())
+    }
+
+    // 去掉行首的空白与注释，返回剩余的内容；in_comment 记录跨行的 /* */ 注释
+    fn skip_comments<'a>(line: &'a str, in_comment: &mut bool) -> &'a str {
+        let mut rest = line.trim_start();
+        loop {
+            if *in_comment {
+                match rest.find("*/") {
+                    Some(end) => {
+                        rest = rest[end + 2..].trim_start();
+                        *in_comment = false;
+                    },
+                    None => return "",
+                }
+            }
+            if rest.starts_with("//") { return ""; }
+            match rest.strip_prefix("/*") {
+                Some(comment) => {
+                    rest = comment;
+                    *in_comment = true;
+                },
+                None => return rest,
+            }
+        }
+    }
+
+    // #include "file" 或 #include <file>
+    fn parse_include(directive: &str) -> Option<&str> {
+        let rest = directive.strip_prefix("#include")?.trim();
+        let (open, close) = match rest.chars().next()? {
+            '"' => ('"', '"'),
+            '<' => ('<', '>'),
+            _ => return None,
+        };
+        let rest = rest.strip_prefix(open)?;
+        let end = rest.find(close)?;
+
+        Some(&rest[..end])
+    }
+}
+
+impl ProcessedSource {
+    /**
+     * 把编译日志中的 "源字符串编号:行号" 或 "源字符串编号(行号)" 替换为文件路径
+     * 兼容 Mesa、AMD 的 "0:12(5)"、"ERROR: 0:12:" 以及 NVIDIA 的 "0(12) :"
+     */
+    pub fn remap_log(&self, log: &str) -> String {
+        log.lines().map(|line| self.remap_line(line)).collect::<Vec<_>>().join("\n")
+    }
+
+    fn remap_line(&self, line: &str) -> String {
+        let bytes = line.as_bytes();
+
+        for start in 0..bytes.len() {
+            if !bytes[start].is_ascii_digit() || (start > 0 && bytes[start - 1].is_ascii_alphanumeric()) { continue; }
+
+            let index_end = start + bytes[start..].iter().take_while(|b| b.is_ascii_digit()).count();
+            let Some(&separator) = bytes.get(index_end) else { break; };
+            if separator != b':' && separator != b'(' { continue; }
+
+            let line_len = bytes[index_end + 1..].iter().take_while(|b| b.is_ascii_digit()).count();
+            if line_len == 0 { continue; }
+
+            // 第一个形如位置的片段编号无效时不再继续查找，以免把行号当作编号
+            let Some(file) = line[start..index_end].parse::<usize>().ok().and_then(|i| self.files.get(i)) else { break; };
+
+            return format!("{}{}{}", &line[..start], file.display(), &line[index_end..]);
+        }
+
+        line.to_string()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    // 每个测试使用独立的临时文件夹，绝对路径会直接从磁盘读取
+    fn temp_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("opengl-rs-preprocessor-{}-{}", std::process::id(), name));
+        for (path, source) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, source).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn defines_follow_version() {
+        let ret = Preprocessor::new().define("USE_FOG", "").define("LIGHTS", "4")
+            .process_source("#version 330 core\nvoid main() {}\n", "main.fs").unwrap();
+
+        assert_eq!(ret.code, "#version 330 core\n#define USE_FOG \n#define LIGHTS 4\n#line 2 0\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn comments_before_version_are_skipped() {
+        let source = "// 单行注释\n/* 多行\n   注释 */\n\n#version 330 core\nvoid main() {}";
+        let ret = Preprocessor::new().define("A", "1").process_source(source, "main.fs").unwrap();
+
+        assert_eq!(ret.code, "#version 330 core\n#define A 1\n#line 6 0\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn version_on_comment_closing_line() {
+        let source = "/* 注释\n */ #version 330 core\nvoid main() {}";
+        let ret = Preprocessor::new().define("A", "1").process_source(source, "main.fs").unwrap();
+
+        assert_eq!(ret.code, "#version 330 core\n#define A 1\n#line 3 0\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn include_emits_line_directives() {
+        let dir = temp_dir("line", &[("a.glsl", "#version 330 core\nfloat a;\nfloat b;")]);
+        let source = "#version 330 core\nfloat x;\n#include \"a.glsl\"\nfloat y;";
+        let ret = Preprocessor::new().process_source(source, dir.join("main.fs")).unwrap();
+
+        // 被包含文件中的 #version 变为空行，包含之后回到 main.fs 的第 4 行
+        assert_eq!(ret.code, "#version 330 core\n#line 2 0\nfloat x;\n#line 1 1\n\nfloat a;\nfloat b;\n#line 4 0\nfloat y;\n");
+        assert_eq!(ret.files, vec![dir.join("main.fs"), dir.join("a.glsl")]);
+    }
+
+    #[test]
+    fn nested_include_resolves_relative_to_including_file() {
+        let dir = temp_dir("nested", &[
+            ("lib/a.glsl", "float a;"),
+            ("lib/inner/b.glsl", "#include \"../a.glsl\"\nfloat b;"),
+        ]);
+        let ret = Preprocessor::new().process_source("#include <lib/inner/b.glsl>", dir.join("main.fs")).unwrap();
+
+        assert_eq!(ret.code, "#line 1 0\n#line 1 1\n#line 1 2\nfloat a;\n#line 2 1\nfloat b;\n#line 2 0\n");
+        assert_eq!(ret.files[2], dir.join("lib/a.glsl"));
+    }
+
+    #[test]
+    fn include_once_compares_normalized_paths() {
+        let dir = temp_dir("once", &[("lighting.glsl", "struct Light { vec3 position; };")]);
+        let source = "#include \"lighting.glsl\"\n#include \"sub/../lighting.glsl\"\n#include \"./lighting.glsl\"\nvoid main() {}";
+        let ret = Preprocessor::new().process_source(source, dir.join("main.fs")).unwrap();
+
+        assert_eq!(ret.code.matches("struct Light").count(), 1);
+        assert_eq!(ret.files.len(), 2);
+        // 跳过的 #include 保留为空行，行号不受影响
+        assert!(ret.code.ends_with("#line 2 0\n\n\nvoid main() {}\n"));
+    }
+
+    #[test]
+    fn entry_file_is_not_included_again() {
+        let dir = temp_dir("entry", &[("a.glsl", "#include \"main.fs\"\nfloat a;")]);
+        let ret = Preprocessor::new().process_source("#include \"a.glsl\"", dir.join("sub/../main.fs")).unwrap();
+
+        assert_eq!(ret.files, vec![dir.join("main.fs"), dir.join("a.glsl")]);
+        assert_eq!(ret.code, "#line 1 0\n#line 1 1\n\nfloat a;\n#line 2 0\n");
+    }
+
+    #[test]
+    fn missing_include_reports_location() {
+        let dir = temp_dir("missing", &[]);
+        let err = Preprocessor::new().process_source("float x;\n#include \"nope.glsl\"", dir.join("main.fs")).unwrap_err();
+
+        match err {
+            ShaderError::PreprocessError(message) => assert!(message.contains("main.fs:2"), "{}", message),
+            err => panic!("unexpected error {:?}", err),
+        }
+    }
+
+    #[test]
+    fn malformed_include_is_an_error() {
+        let err = Preprocessor::new().process_source("#include lighting.glsl", "main.fs").unwrap_err();
+        assert!(matches!(err, ShaderError::PreprocessError(_)));
+    }
+
+    #[test]
+    fn remap_log_replaces_source_indices() {
+        let processed = ProcessedSource { code: String::new(), files: vec![PathBuf::from("main.fs"), PathBuf::from("lighting.glsl")] };
+        let log = [
+            "0:12(5): error: `x' undeclared",
+            "ERROR: 1:3: 'y' : undeclared identifier",
+            "1(7) : error C0000: syntax error",
+            "9:1(1): error: unknown source",
+            "warning: 2 errors",
+        ].join("\n");
+
+        assert_eq!(processed.remap_log(&log), [
+            "main.fs:12(5): error: `x' undeclared",
+            "ERROR: lighting.glsl:3: 'y' : undeclared identifier",
+            "lighting.glsl(7) : error C0000: syntax error",
+            "9:1(1): error: unknown source",
+            "warning: 2 errors",
+        ].join("\n"));
+    }
+}
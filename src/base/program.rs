@@ -1,11 +1,25 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ffi::CString;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use gl::types::{GLuint, GLint};
+use nalgebra_glm as glm;
 
-use crate::base::{shader::Shader, error::ShaderError, utility::{self, CheckType}};
+use crate::base::{shader::{Shader, ShaderCode, ShaderStage}, error::ShaderError, preprocessor::Preprocessor, shader_source, uniform::{self, AttributeInfo, UniformBlockInfo, UniformInfo, UniformValue, Uniforms}, uniform_buffer, utility::{self, CheckType}};
 
 pub struct ShaderProgram {
     pub id: GLuint,
+
+    sources: Vec<(ShaderCode, ShaderStage)>,                    // 着色器源码及阶段
+    preprocessor: Preprocessor,
+    watched: Vec<(PathBuf, Option<SystemTime>)>,                // 源文件及其包含的文件的最后修改时间，用于热重载
+
+    uniforms: Vec<UniformInfo>,                                 // 链接时反射得到的活动 uniform 变量
+    attributes: Vec<AttributeInfo>,                             // 链接时反射得到的活动顶点属性
+    uniform_blocks: Vec<UniformBlockInfo>,                      // 链接时反射得到的活动 uniform 块
+    uniform_cache: RefCell<HashMap<String, UniformInfo>>,       // 按名字缓存的 uniform 变量，包括数组元素
 }
 
 impl Drop for ShaderProgram {
@@ -14,75 +28,349 @@ impl Drop for ShaderProgram {
     }
 }
 
+/**
+ * 着色器程序构建器
+ * ShaderProgram::builder().vertex("a.vs").geometry("a.gs").fragment("a.fs").build()
+ */
+#[derive(Default)]
+pub struct ShaderProgramBuilder {
+    sources: Vec<(ShaderCode, ShaderStage)>,
+    preprocessor: Preprocessor,
+}
+
+impl ShaderProgramBuilder {
+    pub fn vertex(self, path: &str) -> Self { self.stage(ShaderStage::Vertex, path) }
+
+    pub fn tess_control(self, path: &str) -> Self { self.stage(ShaderStage::TessControl, path) }
+
+    pub fn tess_evaluation(self, path: &str) -> Self { self.stage(ShaderStage::TessEvaluation, path) }
+
+    pub fn geometry(self, path: &str) -> Self { self.stage(ShaderStage::Geometry, path) }
+
+    pub fn fragment(self, path: &str) -> Self { self.stage(ShaderStage::Fragment, path) }
+
+    pub fn vertex_source(self, name: &str, code: &str) -> Self { self.stage_source(ShaderStage::Vertex, name, code) }
+
+    pub fn tess_control_source(self, name: &str, code: &str) -> Self { self.stage_source(ShaderStage::TessControl, name, code) }
+
+    pub fn tess_evaluation_source(self, name: &str, code: &str) -> Self { self.stage_source(ShaderStage::TessEvaluation, name, code) }
+
+    pub fn geometry_source(self, name: &str, code: &str) -> Self { self.stage_source(ShaderStage::Geometry, name, code) }
+
+    pub fn fragment_source(self, name: &str, code: &str) -> Self { self.stage_source(ShaderStage::Fragment, name, code) }
+
+    pub fn stage(self, stage: ShaderStage, path: &str) -> Self { self.code(stage, ShaderCode::File(path.into())) }
+
+    // 内存中的源码，name 用于解析 #include 和在错误日志中显示
+    pub fn stage_source(self, stage: ShaderStage, name: &str, code: &str) -> Self {
+        self.code(stage, ShaderCode::Source { name: name.into(), code: code.into() })
+    }
+
+    // 同一阶段重复设置时以最后一次为准
+    pub fn code(mut self, stage: ShaderStage, code: ShaderCode) -> Self {
+        self.sources.retain(|(_, s)| *s != stage);
+        self.sources.push((code, stage));
+        self
+    }
+
+    pub fn define<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.preprocessor.set_define(name, value);
+        self
+    }
+
+    pub fn preprocessor(mut self, preprocessor: Preprocessor) -> Self {
+        self.preprocessor = preprocessor;
+        self
+    }
+
+    /**
+     * 编译并链接
+     * 需要顶点着色器；曲面细分控制着色器需要搭配曲面细分求值着色器；计算着色器请使用 ComputeProgram
+     */
+    pub unsafe fn build(self) -> Result<ShaderProgram, ShaderError> {
+        let has = |stage: ShaderStage| self.sources.iter().any(|(_, s)| *s == stage);
+
+        if has(ShaderStage::Compute) {
+            return Err(ShaderError::InvalidStages("compute shaders must be built with ComputeProgram".into()));
+        }
+        if !has(ShaderStage::Vertex) {
+            return Err(ShaderError::InvalidStages("a vertex shader is required".into()));
+        }
+        if has(ShaderStage::TessControl) && !has(ShaderStage::TessEvaluation) {
+            return Err(ShaderError::InvalidStages("a tessellation control shader requires a tessellation evaluation shader".into()));
+        }
+
+        ShaderProgram::from_stages(self.sources, self.preprocessor)
+    }
+}
+
 #[allow(dead_code)]
 impl ShaderProgram {
     pub unsafe fn new(file_vs: &str, file_fs: &str) -> Result<Self, ShaderError> {
-        let program = Self { id: gl::CreateProgram() };
+        Self::with_preprocessor(file_vs, file_fs, Preprocessor::default())
+    }
 
-        let vertex_shader = Shader::new(file_vs, gl::VERTEX_SHADER)?;
-        let fragment_shader = Shader::new(file_fs, gl::FRAGMENT_SHADER)?;
+    // 编译前注入宏定义，如 &[("MAX_LIGHTS", "4")]
+    pub unsafe fn with_defines(file_vs: &str, file_fs: &str, defines: &[(&str, &str)]) -> Result<Self, ShaderError> {
+        let preprocessor = defines.iter().fold(Preprocessor::new(), |p, (name, value)| p.define(*name, *value));
+        Self::with_preprocessor(file_vs, file_fs, preprocessor)
+    }
 
-        gl::AttachShader(program.id, vertex_shader.id);
-        gl::AttachShader(program.id, fragment_shader.id);
-        
-        gl::LinkProgram(program.id);
+    pub unsafe fn with_preprocessor(file_vs: &str, file_fs: &str, preprocessor: Preprocessor) -> Result<Self, ShaderError> {
+        Self::builder().vertex(file_vs).fragment(file_fs).preprocessor(preprocessor).build()
+    }
 
-        utility::check_compile_error(program.id, CheckType::Program)?;
+    // 由内存中的源码创建
+    pub unsafe fn from_sources(code_vs: &str, code_fs: &str) -> Result<Self, ShaderError> {
+        Self::builder().vertex_source("<vertex>", code_vs).fragment_source("<fragment>", code_fs).build()
+    }
 
+    // 附加任意着色器阶段，如几何着色器、曲面细分着色器
+    pub fn builder() -> ShaderProgramBuilder { ShaderProgramBuilder::default() }
+
+    pub(crate) unsafe fn from_stages(sources: Vec<(ShaderCode, ShaderStage)>, preprocessor: Preprocessor) -> Result<Self, ShaderError> {
+        let (id, files) = Self::link(&sources, &preprocessor)?;
+        let mut program = Self { 
+            id, 
+            sources, 
+            preprocessor, 
+            watched: Self::watch(files), 
+            uniforms: Vec::new(), 
+            attributes: Vec::new(), 
+            uniform_blocks: Vec::new(), 
+            uniform_cache: RefCell::new(HashMap::new()) 
+        };
+
+        program.reflect()?;
         program.apply();
         
         Ok(program)
     }
 
+    /**
+     * 检查源文件（包括 #include 的文件）的修改时间，有变化时重新编译、链接
+     * 失败时保留原来的程序并打印错误日志，成功时返回 true
+     * 新程序中 uniform 变量的值会被重置，需要重新设置
+     */
+    pub unsafe fn reload_if_changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, modified) in &mut self.watched {
+            let current = Self::modified_time(path);
+            if current != *modified {
+                *modified = current;
+                changed = true;
+            }
+        }
+        if !changed { return false; }
+
+        let result = Self::link(&self.sources, &self.preprocessor).and_then(|(id, files)| {
+            let old = std::mem::replace(&mut self.id, id);
+            match self.reflect() {
+                Ok(()) => {
+                    gl::DeleteProgram(old);
+                    Ok(files)
+                },
+                // uniform 块与缓冲不一致时恢复原来的程序
+                Err(err) => {
+                    gl::DeleteProgram(id);
+                    self.id = old;
+                    self.reflect().ok();
+                    Err(err)
+                },
+            }
+        });
+
+        match result {
+            Ok(files) => {
+                self.watched = Self::watch(files);
+                self.apply();
+                true
+            },
+            Err(err) => {
+                let paths: Vec<&str> = self.sources.iter().map(|(code, _)| code.name()).collect();
+                eprintln!("failed to reload shader program [{}]: {}", paths.join(", "), err);
+                false
+            }
+        }
+    }
+
+    // 编译并链接所有着色器，返回程序 ID 以及用到的所有文件
+    unsafe fn link(sources: &[(ShaderCode, ShaderStage)], preprocessor: &Preprocessor) -> Result<(GLuint, Vec<PathBuf>), ShaderError> {
+        let shaders = sources.iter()
+            .map(|(code, stage)| Shader::with_stage(code, *stage, preprocessor))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let id = gl::CreateProgram();
+        for shader in &shaders {
+            gl::AttachShader(id, shader.id);
+        }
+        
+        gl::LinkProgram(id);
+
+        if let Err(err) = utility::check_compile_error(id, CheckType::Program) {
+            gl::DeleteProgram(id);
+            return Err(err);
+        }
+
+        // 内存中的源码不需要监视，只监视其包含的文件
+        let files = sources.iter().zip(&shaders)
+            .flat_map(|((code, _), shader)| shader.files().iter().skip(matches!(code, ShaderCode::Source { .. }) as usize).cloned())
+            .collect();
+        Ok((id, files))
+    }
+
+    fn watch(files: Vec<PathBuf>) -> Vec<(PathBuf, Option<SystemTime>)> {
+        let mut watched: Vec<(PathBuf, Option<SystemTime>)> = Vec::with_capacity(files.len());
+        for file in files {
+            if watched.iter().all(|(path, _)| *path != file) {
+                let modified = Self::modified_time(&file);
+                watched.push((file, modified));
+            }
+        }
+        watched
+    }
+
+    fn modified_time(path: &Path) -> Option<SystemTime> { shader_source::modified_time(path) }
+
+    // 读取活动的 uniform 变量、uniform 块和顶点属性，并重建缓存
+    unsafe fn reflect(&mut self) -> Result<(), ShaderError> {
+        self.uniforms = uniform::reflect_uniforms(self.id);
+        self.attributes = uniform::reflect_attributes(self.id);
+        self.uniform_blocks = uniform::reflect_uniform_blocks(self.id, &self.uniforms);
+
+        // uniform 块绑定到同名的绑定点，与 UniformBuffer 共享，并检查与已创建的缓冲布局是否一致
+        for block in &mut self.uniform_blocks {
+            uniform_buffer::validate_block(block)?;
+            block.binding = uniform_buffer::binding_point(&block.name)?;
+            gl::UniformBlockBinding(self.id, block.index, block.binding);
+        }
+
+        let cache = self.uniform_cache.get_mut();
+        cache.clear();
+        for info in &self.uniforms {
+            // 数组 "name[0]" 也可以直接用 "name" 访问
+            if let Some(base) = info.name.strip_suffix("[0]") {
+                cache.insert(base.into(), info.clone());
+            }
+            cache.insert(info.name.clone(), info.clone());
+        }
+
+        Ok(())
+    }
+
     pub unsafe fn apply(&self) { gl::UseProgram(self.id); }
 
+    // 活动的 uniform 变量，包括 uniform 块中的变量
+    pub fn active_uniforms(&self) -> &[UniformInfo] { &self.uniforms }
+
+    // 活动的 uniform 块
+    pub fn uniform_blocks(&self) -> &[UniformBlockInfo] { &self.uniform_blocks }
+
+    pub fn uniform_block(&self, name: &str) -> Option<&UniformBlockInfo> {
+        self.uniform_blocks.iter().find(|block| block.name == name)
+    }
+
+    // 活动的顶点属性
+    pub fn active_attributes(&self) -> &[AttributeInfo] { &self.attributes }
+
+    /**
+     * 查找 uniform 变量，支持 "light.position"、"kernel"、"kernel[3]"、"lights[1].position" 等形式
+     * 数组元素的 size 为从该元素到数组末尾的长度
+     */
+    pub unsafe fn uniform_info(&self, name: &str) -> Option<UniformInfo> {
+        if let Some(info) = self.uniform_cache.borrow().get(name) {
+            return Some(info.clone());
+        }
+
+        // 数组元素 "name[i]"，由 "name[0]" 推导类型
+        let (base, index) = name.strip_suffix(']')?.rsplit_once('[')?;
+        let index: i32 = index.parse().ok()?;
+        let array = self.uniform_cache.borrow().get(&format!("{}[0]", base))?.clone();
+        if index >= array.size { return None; }
+
+        let cs_name = CString::new(name).ok()?;
+        let info = UniformInfo { 
+            name: name.into(), 
+            gl_type: array.gl_type, 
+            size: array.size - index, 
+            location: gl::GetUniformLocation(self.id, cs_name.as_ptr()) 
+        };
+        self.uniform_cache.borrow_mut().insert(name.into(), info.clone());
+
+        Some(info)
+    }
+
     // 获取属性对应的location ID
     pub unsafe fn get_attr_location(&self, attr: &str) -> Result<GLuint, ShaderError> {
-        let cs_attr = CString::new(attr)?;
-        let ret = gl::GetAttribLocation(self.id, cs_attr.as_ptr());
-        match ret {
-            -1 => Err(ShaderError::AttributeLocationParseError(attr.into())),
-            value => Ok(value as GLuint)
+        match self.attributes.iter().find(|info| info.name == attr) {
+            Some(info) if info.location >= 0 => Ok(info.location as GLuint),
+            _ => Err(ShaderError::AttributeLocationParseError(attr.into())),
         }
     }
 
     // 获取uniform变量的location ID
     pub unsafe fn get_uniform_location(&self, name: &str) -> Result<GLint, ShaderError> {
-        let cs_name = CString::new(name)?;
-        let ret = gl::GetUniformLocation(self.id, cs_name.as_ptr());
-        match ret {
-            -1 => Err(ShaderError::UniformLocationParseError(name.into())),
-            value => Ok(value)
+        match self.uniform_info(name) {
+            Some(info) if info.location >= 0 => Ok(info.location),
+            _ => Err(ShaderError::UniformLocationParseError(name.into())),
         }
     }
 
-    pub unsafe fn set_int(&self, name: &str, value: i32) -> Result<(), ShaderError> {
-        self.apply();
-        gl::Uniform1i(self.get_uniform_location(name)?, value);
-        Ok(())
+    /**
+     * 设置 uniform 变量，Rust 类型与 GLSL 类型不匹配时返回错误
+     * 如 set_uniform("model", &glm::Mat4::identity())、set_uniform("light.position", &glm::vec3(0.0, 1.0, 0.0))
+     */
+    pub unsafe fn set_uniform<T: UniformValue>(&self, name: &str, value: &T) -> Result<(), ShaderError> {
+        self.set_uniform_array(name, std::slice::from_ref(value))
+    }
+
+    // 上传整个结构体，如 set_uniforms("light", &light)
+    pub unsafe fn set_uniforms<T: Uniforms>(&self, name: &str, value: &T) -> Result<(), ShaderError> {
+        value.set_uniforms(self, name)
     }
 
-    pub unsafe fn set_float(&self, name: &str, value: f32) -> Result<(), ShaderError> {
+    // 从 name 开始设置数组的 values.len() 个元素，超出数组长度时返回错误
+    pub unsafe fn set_uniform_array<T: UniformValue>(&self, name: &str, values: &[T]) -> Result<(), ShaderError> {
+        let info = self.uniform_info(name).filter(|info| info.location >= 0)
+            .ok_or_else(|| ShaderError::UniformLocationParseError(name.into()))?;
+
+        if !T::accepts(info.gl_type) {
+            return Err(ShaderError::UniformTypeMismatch(name.into(), uniform::gl_type_name(info.gl_type), T::RUST_TYPE));
+        }
+        if values.len() > info.size as usize {
+            return Err(ShaderError::UniformArrayOverflow(name.into(), values.len(), info.size as usize));
+        }
+
         self.apply();
-        gl::Uniform1f(self.get_uniform_location(name)?, value);
+        T::upload(info.location, values);
         Ok(())
     }
 
+    // 以下的 set_* 都通过 set_uniform 设置，同样会检查类型
+    pub unsafe fn set_int(&self, name: &str, value: i32) -> Result<(), ShaderError> { self.set_uniform(name, &value) }
+
+    pub unsafe fn set_uint(&self, name: &str, value: u32) -> Result<(), ShaderError> { self.set_uniform(name, &value) }
+
+    pub unsafe fn set_float(&self, name: &str, value: f32) -> Result<(), ShaderError> { self.set_uniform(name, &value) }
+
+    // value 为按列主序排列的 16 个元素，如 glm::value_ptr(&matrix)
     pub unsafe fn set_mat4(&self, name: &str, value: &[f32]) -> Result<(), ShaderError> {
-        self.apply();
-        gl::UniformMatrix4fv(self.get_uniform_location(name)?, 1, gl::FALSE, &value[0]);
-        Ok(())
+        Self::check_len(name, value, 16)?;
+        self.set_uniform(name, &glm::make_mat4(value))
     }
 
     pub unsafe fn set_vec3(&self, name: &str, value: &[f32]) -> Result<(), ShaderError> {
-        self.apply();
-        gl::Uniform3fv(self.get_uniform_location(name)?, 1, &value[0]);
-        Ok(())
+        Self::check_len(name, value, 3)?;
+        self.set_uniform(name, &glm::make_vec3(value))
     }
 
     pub unsafe fn set_vec4(&self, name: &str, value: &[f32]) -> Result<(), ShaderError> {
-        self.apply();
-        gl::Uniform4fv(self.get_uniform_location(name)?, 1, &value[0]);
+        Self::check_len(name, value, 4)?;
+        self.set_uniform(name, &glm::make_vec4(value))
+    }
+
+    fn check_len(name: &str, value: &[f32], expected: usize) -> Result<(), ShaderError> {
+        if value.len() != expected { return Err(ShaderError::UniformValueLength(name.into(), expected, value.len())); }
         Ok(())
     }
 }
\ No newline at end of file
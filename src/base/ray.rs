@@ -0,0 +1,223 @@
+#![allow(dead_code)]
+
+use nalgebra_glm as glm;
+
+use crate::base::camera::Camera;
+use crate::base::frustum::Aabb;
+
+/**
+ * 射线 origin + t * direction，t >= 0
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: glm::Vec3,
+    pub direction: glm::Vec3,
+}
+
+/**
+ * 射线与模型的交点
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub mesh: usize,                            // Model::meshes 中的下标
+    pub triangle: usize,                        // 网格中的第几个三角形，顶点为 indices[3 * triangle..3 * triangle + 3]
+    pub point: glm::Vec3,                       // 世界空间中的交点
+    pub distance: f32,                          // 沿射线的距离
+}
+
+impl Ray {
+    pub fn new(origin: glm::Vec3, direction: glm::Vec3) -> Self {
+        Self { origin, direction: glm::normalize(&direction) }
+    }
+
+    /**
+     * 根据光标位置生成世界空间的射线
+     * cursor: 光标位置，左上角为原点
+     * size: 窗口大小，需要与 cursor 使用同一坐标系
+     */
+    pub fn from_screen(camera: &Camera, cursor: (f64, f64), size: (u32, u32)) -> Self {
+        let ndc_x = (2.0 * cursor.0 / size.0.max(1) as f64 - 1.0) as f32;
+        let ndc_y = (1.0 - 2.0 * cursor.1 / size.1.max(1) as f64) as f32;
+
+        let inverse = glm::inverse(&camera.get_view_projection_matrix());
+        let unproject = |z: f32| {
+            let p = inverse * glm::vec4(ndc_x, ndc_y, z, 1.0);
+            p.xyz() / p.w
+        };
+
+        let near = unproject(-1.0);
+        let far = unproject(1.0);
+        Self::new(near, far - near)
+    }
+
+    pub fn at(&self, t: f32) -> glm::Vec3 { self.origin + self.direction * t }
+
+    // 变换射线，方向不重新归一化，所以变换前后同一个 t 对应同一个点
+    pub fn transform(&self, matrix: &glm::Mat4) -> Ray {
+        let origin = matrix * glm::vec4(self.origin.x, self.origin.y, self.origin.z, 1.0);
+        let direction = matrix * glm::vec4(self.direction.x, self.direction.y, self.direction.z, 0.0);
+
+        Ray { origin: origin.xyz() / origin.w, direction: direction.xyz() }
+    }
+
+    // slab 法，返回最近的交点距离，射线起点在包围盒内时返回 0
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let mut t_min = 0.0_f32;
+        let mut t_max = f32::INFINITY;
+
+        for i in 0..3 {
+            let inv = 1.0 / self.direction[i];
+            let mut t0 = (aabb.min[i] - self.origin[i]) * inv;
+            let mut t1 = (aabb.max[i] - self.origin[i]) * inv;
+            if inv < 0.0 { std::mem::swap(&mut t0, &mut t1); }
+
+            // 射线与该轴平行并且起点恰好在平面上时会得到 NaN，这里视为相交
+            if !t0.is_nan() { t_min = t_min.max(t0); }
+            if !t1.is_nan() { t_max = t_max.min(t1); }
+            if t_max < t_min { return None; }
+        }
+
+        Some(t_min)
+    }
+
+    // Möller–Trumbore 算法，双面相交，返回交点距离
+    pub fn intersect_triangle(&self, a: &glm::Vec3, b: &glm::Vec3, c: &glm::Vec3) -> Option<f32> {
+        const EPSILON: f32 = 1e-7;
+
+        let edge1 = b - a;
+        let edge2 = c - a;
+        let p = glm::cross(&self.direction, &edge2);
+        let det = glm::dot(&edge1, &p);
+        if det.abs() < EPSILON { return None; }
+
+        let inv_det = 1.0 / det;
+        let s = self.origin - a;
+        let u = glm::dot(&s, &p) * inv_det;
+        if !(0.0..=1.0).contains(&u) { return None; }
+
+        let q = glm::cross(&s, &edge1);
+        let v = glm::dot(&self.direction, &q) * inv_det;
+        if v < 0.0 || u + v > 1.0 { return None; }
+
+        let t = glm::dot(&edge2, &q) * inv_det;
+        if t >= 0.0 { Some(t) } else { None }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    fn unit_box() -> Aabb { Aabb::new(glm::vec3(-1.0, -1.0, -1.0), glm::vec3(1.0, 1.0, 1.0)) }
+
+    fn triangle() -> (glm::Vec3, glm::Vec3, glm::Vec3) {
+        (glm::vec3(-1.0, -1.0, 0.0), glm::vec3(1.0, -1.0, 0.0), glm::vec3(0.0, 1.0, 0.0))
+    }
+
+    #[test]
+    fn aabb_hit_returns_entry_distance() {
+        let ray = Ray::new(glm::vec3(0.0, 0.0, 5.0), glm::vec3(0.0, 0.0, -1.0));
+        let t = ray.intersect_aabb(&unit_box()).unwrap();
+
+        assert!((t - 4.0).abs() < EPSILON);
+        assert!(glm::distance(&ray.at(t), &glm::vec3(0.0, 0.0, 1.0)) < EPSILON);
+    }
+
+    #[test]
+    fn aabb_diagonal_hit() {
+        let ray = Ray::new(glm::vec3(3.0, 3.0, 3.0), glm::vec3(-1.0, -1.0, -1.0));
+        let t = ray.intersect_aabb(&unit_box()).unwrap();
+
+        assert!((t - 2.0 * 3_f32.sqrt()).abs() < EPSILON);
+    }
+
+    #[test]
+    fn aabb_miss() {
+        let ray = Ray::new(glm::vec3(0.0, 3.0, 5.0), glm::vec3(0.0, 0.0, -1.0));
+        assert!(ray.intersect_aabb(&unit_box()).is_none());
+
+        let ray = Ray::new(glm::vec3(0.0, 0.0, 5.0), glm::vec3(1.0, 0.0, -0.5));
+        assert!(ray.intersect_aabb(&unit_box()).is_none());
+    }
+
+    #[test]
+    fn aabb_ray_parallel_to_face() {
+        // 与 x 轴平行，在 y、z 的 slab 之内
+        let ray = Ray::new(glm::vec3(-5.0, 0.5, 0.5), glm::vec3(1.0, 0.0, 0.0));
+        assert!((ray.intersect_aabb(&unit_box()).unwrap() - 4.0).abs() < EPSILON);
+
+        // 与 x 轴平行，在 y 的 slab 之外
+        let ray = Ray::new(glm::vec3(-5.0, 2.0, 0.5), glm::vec3(1.0, 0.0, 0.0));
+        assert!(ray.intersect_aabb(&unit_box()).is_none());
+
+        // 沿着面滑过
+        let ray = Ray::new(glm::vec3(-5.0, 1.0, 0.0), glm::vec3(1.0, 0.0, 0.0));
+        assert!(ray.intersect_aabb(&unit_box()).is_some());
+    }
+
+    #[test]
+    fn aabb_origin_inside_returns_zero() {
+        let ray = Ray::new(glm::vec3(0.2, -0.3, 0.5), glm::vec3(0.3, 1.0, -0.2));
+        assert_eq!(ray.intersect_aabb(&unit_box()), Some(0.0));
+    }
+
+    #[test]
+    fn aabb_behind_origin_is_missed() {
+        let ray = Ray::new(glm::vec3(0.0, 0.0, 5.0), glm::vec3(0.0, 0.0, 1.0));
+        assert!(ray.intersect_aabb(&unit_box()).is_none());
+    }
+
+    #[test]
+    fn triangle_hit_from_both_sides() {
+        let (a, b, c) = triangle();
+
+        let ray = Ray::new(glm::vec3(0.0, 0.0, 2.0), glm::vec3(0.0, 0.0, -1.0));
+        assert!((ray.intersect_triangle(&a, &b, &c).unwrap() - 2.0).abs() < EPSILON);
+
+        let ray = Ray::new(glm::vec3(0.0, 0.0, -3.0), glm::vec3(0.0, 0.0, 1.0));
+        assert!((ray.intersect_triangle(&a, &b, &c).unwrap() - 3.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn triangle_miss_outside_edges() {
+        let (a, b, c) = triangle();
+
+        // 在包围盒内但在三角形外
+        let ray = Ray::new(glm::vec3(0.9, 0.9, 2.0), glm::vec3(0.0, 0.0, -1.0));
+        assert!(ray.intersect_triangle(&a, &b, &c).is_none());
+
+        let ray = Ray::new(glm::vec3(0.0, -1.5, 2.0), glm::vec3(0.0, 0.0, -1.0));
+        assert!(ray.intersect_triangle(&a, &b, &c).is_none());
+    }
+
+    #[test]
+    fn triangle_parallel_ray_is_missed() {
+        let (a, b, c) = triangle();
+        let ray = Ray::new(glm::vec3(-5.0, 0.0, 0.0), glm::vec3(1.0, 0.0, 0.0));
+
+        assert!(ray.intersect_triangle(&a, &b, &c).is_none());
+    }
+
+    #[test]
+    fn triangle_behind_origin_is_missed() {
+        let (a, b, c) = triangle();
+        let ray = Ray::new(glm::vec3(0.0, 0.0, 2.0), glm::vec3(0.0, 0.0, 1.0));
+
+        assert!(ray.intersect_triangle(&a, &b, &c).is_none());
+    }
+
+    #[test]
+    fn transform_keeps_distance() {
+        let ray = Ray::new(glm::vec3(0.0, 0.0, 5.0), glm::vec3(0.0, 0.0, -1.0));
+        // 把射线变换到缩放为 2 倍的模型空间
+        let inverse = glm::inverse(&glm::scale(&glm::Mat4::identity(), &glm::vec3(2.0, 2.0, 2.0)));
+        let local = ray.transform(&inverse);
+
+        let t = local.intersect_aabb(&unit_box()).unwrap();
+        assert!((t - 3.0).abs() < EPSILON);
+        assert!(glm::distance(&ray.at(t), &glm::vec3(0.0, 0.0, 2.0)) < EPSILON);
+    }
+}
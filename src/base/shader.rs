@@ -1,11 +1,80 @@
-use std::{fs, ptr, ffi::CString};
+use std::{ptr, ffi::CString, path::PathBuf};
 
 use gl::types::{GLuint, GLenum };
 
-use crate::base::{error::ShaderError, utility::{self, CheckType}};
+use crate::base::{error::ShaderError, preprocessor::{Preprocessor, ProcessedSource}, utility::{self, CheckType}};
+
+/**
+ * 着色器阶段
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShaderStage { Vertex, TessControl, TessEvaluation, Geometry, Fragment, Compute }
+
+impl ShaderStage {
+    pub fn gl_type(&self) -> GLenum {
+        match self {
+            ShaderStage::Vertex => gl::VERTEX_SHADER,
+            ShaderStage::TessControl => gl::TESS_CONTROL_SHADER,
+            ShaderStage::TessEvaluation => gl::TESS_EVALUATION_SHADER,
+            ShaderStage::Geometry => gl::GEOMETRY_SHADER,
+            ShaderStage::Fragment => gl::FRAGMENT_SHADER,
+            ShaderStage::Compute => gl::COMPUTE_SHADER,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShaderStage::Vertex => "vertex",
+            ShaderStage::TessControl => "tessellation control",
+            ShaderStage::TessEvaluation => "tessellation evaluation",
+            ShaderStage::Geometry => "geometry",
+            ShaderStage::Fragment => "fragment",
+            ShaderStage::Compute => "compute",
+        }
+    }
+
+    // 支持该阶段所需的最低 OpenGL 版本
+    pub fn required_version(&self) -> (u32, u32) {
+        match self {
+            ShaderStage::Vertex | ShaderStage::Fragment => (2, 0),
+            ShaderStage::Geometry => (3, 2),
+            ShaderStage::TessControl | ShaderStage::TessEvaluation => (4, 0),
+            ShaderStage::Compute => (4, 3),
+        }
+    }
+
+    // 当前上下文是否支持该阶段
+    pub unsafe fn check_supported(&self) -> Result<(), ShaderError> {
+        let current = utility::context_version();
+        let required = self.required_version();
+        if current >= required { return Ok(()); }
+
+        Err(ShaderError::UnsupportedStage(self.name(), required.0, required.1, current.0, current.1))
+    }
+}
+
+/**
+ * 着色器源码的来源
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShaderCode {
+    File(String),                               // 文件路径，通过 shader_source 读取
+    Source { name: String, code: String },      // 内存中的源码，name 用于解析 #include 和在错误日志中显示
+}
+
+impl ShaderCode {
+    pub fn name(&self) -> &str {
+        match self {
+            ShaderCode::File(path) => path,
+            ShaderCode::Source { name, .. } => name,
+        }
+    }
+}
 
 pub struct Shader {
     pub id: GLuint,
+
+    files: Vec<PathBuf>,                        // 入口文件及其包含的文件
 }
 
 impl Drop for Shader {
@@ -16,16 +85,44 @@ impl Drop for Shader {
 
 impl Shader {
     pub unsafe fn new(source_path: &str, shader_type: GLenum) -> Result<Self, ShaderError> {
-        let source = CString::new(fs::read_to_string(source_path)?)?;
-        let shader = Self{ id: gl::CreateShader(shader_type) };
+        Self::with_preprocessor(source_path, shader_type, &Preprocessor::default())
+    }
+
+    // 编译前检查当前上下文是否支持该阶段
+    pub unsafe fn with_stage(code: &ShaderCode, stage: ShaderStage, preprocessor: &Preprocessor) -> Result<Self, ShaderError> {
+        stage.check_supported()?;
+        let source = match code {
+            ShaderCode::File(path) => preprocessor.process(path)?,
+            ShaderCode::Source { name, code } => preprocessor.process_source(code, name)?,
+        };
+
+        Self::from_source(&source, stage.gl_type())
+    }
 
-        gl::ShaderSource(shader.id, 1, &source.as_ptr(), ptr::null());
+    // 编译内存中的源码
+    pub unsafe fn from_str(code: &str, shader_type: GLenum) -> Result<Self, ShaderError> {
+        Self::from_source(&Preprocessor::default().process_source(code, "")?, shader_type)
+    }
+
+    pub unsafe fn with_preprocessor(source_path: &str, shader_type: GLenum, preprocessor: &Preprocessor) -> Result<Self, ShaderError> {
+        Self::from_source(&preprocessor.process(source_path)?, shader_type)
+    }
+
+    // 编译已经预处理过的源码，错误日志中的行号会映射回原始文件
+    pub unsafe fn from_source(source: &ProcessedSource, shader_type: GLenum) -> Result<Self, ShaderError> {
+        let code = CString::new(source.code.as_str())?;
+        let shader = Self{ id: gl::CreateShader(shader_type), files: source.files.clone() };
+
+        gl::ShaderSource(shader.id, 1, &code.as_ptr(), ptr::null());
         gl::CompileShader(shader.id);
 
-        utility::check_compile_error(shader.id, CheckType::Shader)?;
+        utility::check_compile_error(shader.id, CheckType::Shader).map_err(|err| match err {
+            ShaderError::CompilationError(log) => ShaderError::CompilationError(source.remap_log(&log)),
+            err => err,
+        })?;
 
         Ok(shader)
     }
 
-    
-}
\ No newline at end of file
+    pub fn files(&self) -> &[PathBuf] { &self.files }
+}
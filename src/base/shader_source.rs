@@ -0,0 +1,119 @@
+#![allow(dead_code)]
+
+use std::borrow::Cow;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+// 编译时由 build.rs 生成，包含 glsl 文件夹下的所有文件
+include!(concat!(env!("OUT_DIR"), "/embedded_shaders.rs"));
+
+// 设置磁盘覆盖目录的环境变量
+const OVERRIDE_DIR_ENV: &str = "OPENGL_RS_SHADER_DIR";
+
+enum Override {
+    Default,                                    // 环境变量指定的目录；未设置时 debug 构建为当前目录，release 构建不覆盖
+    Dir(PathBuf),
+    Disabled,
+}
+
+static OVERRIDE: RwLock<Override> = RwLock::new(Override::Default);
+
+/**
+ * 设置磁盘覆盖目录，该目录下存在同一路径的文件时优先于嵌入的着色器，便于热重载
+ * None 表示只使用嵌入的着色器（嵌入表中没有的文件仍从磁盘读取）
+ */
+pub fn set_override_dir<P: AsRef<Path>>(dir: Option<P>) {
+    let value = match dir {
+        Some(dir) => Override::Dir(dir.as_ref().to_path_buf()),
+        None => Override::Disabled,
+    };
+    *OVERRIDE.write().unwrap() = value;
+}
+
+pub fn override_dir() -> Option<PathBuf> {
+    match &*OVERRIDE.read().unwrap() {
+        Override::Default => env::var_os(OVERRIDE_DIR_ENV).map(PathBuf::from)
+            .or_else(|| cfg!(debug_assertions).then(|| PathBuf::from("."))),
+        Override::Dir(dir) => Some(dir.clone()),
+        Override::Disabled => None,
+    }
+}
+
+// 嵌入的着色器，路径形如 "glsl/cube/vertex.glsl"
+pub fn embedded<P: AsRef<Path>>(path: P) -> Option<&'static str> {
+    let path = normalize(path.as_ref());
+    EMBEDDED_SHADERS.iter().find(|(name, _)| Path::new(name) == path).map(|(_, source)| *source)
+}
+
+/**
+ * 读取着色器源码，依次查找：覆盖目录、嵌入的着色器、磁盘上的原路径
+ */
+pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Cow<'static, str>> {
+    let path = path.as_ref();
+    if let Some(file) = override_file(path) {
+        return fs::read_to_string(file).map(Cow::Owned);
+    }
+    if let Some(source) = embedded(path) {
+        return Ok(Cow::Borrowed(source));
+    }
+
+    fs::read_to_string(path).map(Cow::Owned)
+}
+
+// 实际读取的磁盘文件的修改时间，使用嵌入的着色器时返回 None
+pub fn modified_time<P: AsRef<Path>>(path: P) -> Option<SystemTime> {
+    let path = path.as_ref();
+    let file = match override_file(path) {
+        Some(file) => file,
+        None if embedded(path).is_some() => return None,
+        None => path.to_path_buf(),
+    };
+
+    fs::metadata(file).and_then(|m| m.modified()).ok()
+}
+
+fn override_file(path: &Path) -> Option<PathBuf> {
+    let file = override_dir()?.join(path);
+    file.is_file().then_some(file)
+}
+
+// 去掉路径中的 "." 和 ".."，不访问文件系统；无法再向上的 ".." 会被保留
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut ret = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => match ret.components().next_back() {
+                Some(Component::Normal(_)) => { ret.pop(); },
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {},
+                _ => ret.push(".."),
+            },
+            component => ret.push(component),
+        }
+    }
+    ret
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_removes_dots() {
+        assert_eq!(normalize(Path::new("glsl/a/../include/./lighting.glsl")), PathBuf::from("glsl/include/lighting.glsl"));
+        assert_eq!(normalize(Path::new("./a/b/../../c.glsl")), PathBuf::from("c.glsl"));
+        assert_eq!(normalize(Path::new("/glsl/../a.glsl")), PathBuf::from("/a.glsl"));
+    }
+
+    #[test]
+    fn normalize_keeps_leading_parent_dirs() {
+        assert_eq!(normalize(Path::new("../shaders/a.glsl")), PathBuf::from("../shaders/a.glsl"));
+        assert_eq!(normalize(Path::new("a/../../b.glsl")), PathBuf::from("../b.glsl"));
+        assert_eq!(normalize(Path::new("/..")), PathBuf::from("/"));
+    }
+}
@@ -0,0 +1,124 @@
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::{BitOr, BitOrAssign};
+use std::rc::Rc;
+
+use crate::base::error::ShaderError;
+use crate::base::mesh::MeshTexture;
+use crate::base::preprocessor::Preprocessor;
+use crate::base::program::ShaderProgram;
+
+/**
+ * 着色器特性开关，每个开启的特性在编译时会定义一个同名的宏，值为 1
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ShaderFeatures(u32);
+
+impl ShaderFeatures {
+    pub const NONE: Self = Self(0);
+    pub const HAS_DIFFUSE_MAP: Self = Self(1 << 0);
+    pub const HAS_SPECULAR_MAP: Self = Self(1 << 1);
+    pub const HAS_NORMAL_MAP: Self = Self(1 << 2);
+    pub const SKINNED: Self = Self(1 << 3);
+
+    // 特性与宏名的对应关系
+    const NAMES: [(Self, &'static str); 4] = [
+        (Self::HAS_DIFFUSE_MAP, "HAS_DIFFUSE_MAP"),
+        (Self::HAS_SPECULAR_MAP, "HAS_SPECULAR_MAP"),
+        (Self::HAS_NORMAL_MAP, "HAS_NORMAL_MAP"),
+        (Self::SKINNED, "SKINNED"),
+    ];
+
+    pub fn contains(&self, other: Self) -> bool { self.0 & other.0 == other.0 }
+
+    pub fn is_empty(&self) -> bool { self.0 == 0 }
+
+    // 根据网格拥有的纹理类型确定特性
+    pub fn from_textures(textures: &[MeshTexture]) -> Self {
+        textures.iter().fold(Self::NONE, |features, texture| features | match texture.type_.as_str() {
+            "texture_diffuse" => Self::HAS_DIFFUSE_MAP,
+            "texture_specular" => Self::HAS_SPECULAR_MAP,
+            "texture_normal" => Self::HAS_NORMAL_MAP,
+            _ => Self::NONE,
+        })
+    }
+
+    // 开启的特性对应的宏名
+    pub fn defines(&self) -> impl Iterator<Item = &'static str> + '_ {
+        Self::NAMES.iter().filter(|(feature, _)| self.contains(*feature)).map(|(_, name)| *name)
+    }
+}
+
+impl BitOr for ShaderFeatures {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self { Self(self.0 | rhs.0) }
+}
+
+impl BitOrAssign for ShaderFeatures {
+    fn bitor_assign(&mut self, rhs: Self) { self.0 |= rhs.0; }
+}
+
+/**
+ * 同一对着色器文件在不同特性组合下的变体，首次使用时编译并缓存
+ */
+pub struct ShaderVariants {
+    file_vs: String,
+    file_fs: String,
+    preprocessor: Preprocessor,                                 // 所有变体共用的宏定义
+
+    cache: RefCell<HashMap<ShaderFeatures, Rc<ShaderProgram>>>,
+}
+
+impl ShaderVariants {
+    pub fn new(file_vs: &str, file_fs: &str) -> Self {
+        Self::with_preprocessor(file_vs, file_fs, Preprocessor::default())
+    }
+
+    pub fn with_preprocessor(file_vs: &str, file_fs: &str, preprocessor: Preprocessor) -> Self {
+        Self { file_vs: file_vs.into(), file_fs: file_fs.into(), preprocessor, cache: RefCell::new(HashMap::new()) }
+    }
+
+    // 获取特性组合对应的程序，不存在时编译；可以同时持有多个变体
+    pub unsafe fn get(&self, features: ShaderFeatures) -> Result<Rc<ShaderProgram>, ShaderError> {
+        if let Some(program) = self.cache.borrow().get(&features) {
+            return Ok(Rc::clone(program));
+        }
+
+        let preprocessor = features.defines().fold(self.preprocessor.clone(), |p, name| p.define(name, "1"));
+        let program = Rc::new(ShaderProgram::with_preprocessor(&self.file_vs, &self.file_fs, preprocessor)?);
+        self.cache.borrow_mut().insert(features, Rc::clone(&program));
+
+        Ok(program)
+    }
+
+    // 提前编译，避免第一次绘制时卡顿
+    pub unsafe fn prepare(&self, features: &[ShaderFeatures]) -> Result<(), ShaderError> {
+        for feature in features {
+            self.get(*feature)?;
+        }
+        Ok(())
+    }
+
+    // 已编译的变体数量
+    pub fn len(&self) -> usize { self.cache.borrow().len() }
+
+    pub fn is_empty(&self) -> bool { self.cache.borrow().is_empty() }
+
+    // 清空缓存，下次使用时重新编译
+    pub fn clear(&mut self) { self.cache.get_mut().clear(); }
+
+    /**
+     * 重新加载源文件有修改的变体，有任一变体重新加载成功时返回 true
+     * 仍被 get 的调用者持有的变体会跳过，下次调用时再检查
+     */
+    pub unsafe fn reload_if_changed(&mut self) -> bool {
+        let mut reloaded = false;
+        for program in self.cache.get_mut().values_mut().filter_map(Rc::get_mut) {
+            reloaded |= program.reload_if_changed();
+        }
+        reloaded
+    }
+}
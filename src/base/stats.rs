@@ -0,0 +1,195 @@
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+
+use gl::types::{GLuint, GLint, GLuint64};
+
+// 统计最近多少帧的数据
+const DEFAULT_CAPACITY: usize = 240;
+// GPU 计时查询的个数，结果通常会延迟几帧才可用，轮流使用避免等待
+const QUERY_COUNT: usize = 4;
+
+/**
+ * 最近若干帧的耗时统计，单位为毫秒
+ */
+#[derive(Debug, Clone)]
+pub struct FrameStats {
+    capacity: usize,
+    frame_times: VecDeque<f32>,             // 相邻两帧的间隔
+    cpu_times: VecDeque<f32>,               // 处理事件、更新与提交绘制命令的耗时
+    gpu_times: VecDeque<f32>,               // GPU 执行绘制命令的耗时
+}
+
+impl Default for FrameStats {
+    fn default() -> Self { Self::new(DEFAULT_CAPACITY) }
+}
+
+impl FrameStats {
+    pub fn new(capacity: usize) -> Self {
+        Self { 
+            capacity, 
+            frame_times: VecDeque::with_capacity(capacity), 
+            cpu_times: VecDeque::with_capacity(capacity), 
+            gpu_times: VecDeque::with_capacity(capacity) 
+        }
+    }
+
+    pub fn fps(&self) -> f32 {
+        let total: f32 = self.frame_times.iter().sum();
+        if total > 0.0 { self.frame_times.len() as f32 * 1000.0 / total } else { 0.0 }
+    }
+
+    pub fn frame_time(&self) -> f32 { average(&self.frame_times) }
+
+    pub fn cpu_time(&self) -> f32 { average(&self.cpu_times) }
+
+    pub fn gpu_time(&self) -> f32 { average(&self.gpu_times) }
+
+    // p 为百分位，范围 [0, 100]，例如 99 表示 99% 的帧耗时不超过该值
+    pub fn frame_time_percentile(&self, p: f32) -> f32 { percentile(&self.frame_times, p) }
+
+    pub fn cpu_time_percentile(&self, p: f32) -> f32 { percentile(&self.cpu_times, p) }
+
+    pub fn gpu_time_percentile(&self, p: f32) -> f32 { percentile(&self.gpu_times, p) }
+
+    pub fn clear(&mut self) {
+        self.frame_times.clear();
+        self.cpu_times.clear();
+        self.gpu_times.clear();
+    }
+
+    pub(crate) fn push_frame_time(&mut self, ms: f32) { Self::push(&mut self.frame_times, self.capacity, ms); }
+
+    pub(crate) fn push_cpu_time(&mut self, ms: f32) { Self::push(&mut self.cpu_times, self.capacity, ms); }
+
+    pub(crate) fn push_gpu_time(&mut self, ms: f32) { Self::push(&mut self.gpu_times, self.capacity, ms); }
+
+    fn push(samples: &mut VecDeque<f32>, capacity: usize, value: f32) {
+        if samples.len() == capacity { samples.pop_front(); }
+        samples.push_back(value);
+    }
+}
+
+/**
+ * 使用 GL_TIME_ELAPSED 查询测量 GPU 耗时
+ */
+pub struct GpuTimer {
+    queries: [GLuint; QUERY_COUNT],
+    is_pending: [bool; QUERY_COUNT],        // 查询已提交但还没有读取结果
+    current: usize,
+}
+
+impl Drop for GpuTimer {
+    fn drop(&mut self) {
+        unsafe { gl::DeleteQueries(QUERY_COUNT as i32, self.queries.as_ptr()); }
+    }
+}
+
+impl GpuTimer {
+    pub unsafe fn new() -> Self {
+        let mut ret = Self { queries: [0; QUERY_COUNT], is_pending: [false; QUERY_COUNT], current: 0 };
+        gl::GenQueries(QUERY_COUNT as i32, ret.queries.as_mut_ptr());
+        ret
+    }
+
+    /**
+     * 开始计时，返回之前某一帧已经可用的耗时（毫秒）
+     * 结果还不可用时会跳过这一次的测量
+     */
+    pub unsafe fn begin(&mut self) -> Option<f32> {
+        let mut ret = None;
+        let query = self.queries[self.current];
+
+        if self.is_pending[self.current] {
+            let mut available: GLint = 0;
+            gl::GetQueryObjectiv(query, gl::QUERY_RESULT_AVAILABLE, &mut available);
+
+            if available != 0 {
+                let mut elapsed: GLuint64 = 0;
+                gl::GetQueryObjectui64v(query, gl::QUERY_RESULT, &mut elapsed);
+                ret = Some(elapsed as f32 / 1_000_000.0);
+            }
+            self.is_pending[self.current] = false;
+        }
+
+        gl::BeginQuery(gl::TIME_ELAPSED, query);
+        ret
+    }
+
+    pub unsafe fn end(&mut self) {
+        gl::EndQuery(gl::TIME_ELAPSED);
+        self.is_pending[self.current] = true;
+        self.current = (self.current + 1) % QUERY_COUNT;
+    }
+}
+
+fn average(samples: &VecDeque<f32>) -> f32 {
+    if samples.is_empty() { return 0.0; }
+    samples.iter().sum::<f32>() / samples.len() as f32
+}
+
+fn percentile(samples: &VecDeque<f32>, p: f32) -> f32 {
+    if samples.is_empty() { return 0.0; }
+
+    let mut sorted: Vec<f32> = samples.iter().copied().collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    let index = (p.clamp(0.0, 100.0) / 100.0 * (sorted.len() - 1) as f32).round() as usize;
+    sorted[index]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_of_known_samples() {
+        let mut stats = FrameStats::new(200);
+        // 0..=100 打乱顺序后加入，第 p 百分位正好是 p
+        for i in 0..=100 { stats.push_frame_time(((i * 37) % 101) as f32); }
+
+        assert_eq!(stats.frame_time_percentile(0.0), 0.0);
+        assert_eq!(stats.frame_time_percentile(50.0), 50.0);
+        assert_eq!(stats.frame_time_percentile(99.0), 99.0);
+        assert_eq!(stats.frame_time_percentile(100.0), 100.0);
+        // 超出范围的百分位会被截断
+        assert_eq!(stats.frame_time_percentile(150.0), 100.0);
+        assert_eq!(stats.frame_time_percentile(-1.0), 0.0);
+    }
+
+    #[test]
+    fn percentile_rounds_to_nearest_sample() {
+        let mut stats = FrameStats::new(8);
+        for ms in [40.0, 10.0, 30.0, 20.0] { stats.push_cpu_time(ms); }
+
+        // 索引为 p / 100 * (n - 1) 四舍五入
+        assert_eq!(stats.cpu_time_percentile(33.0), 20.0);
+        assert_eq!(stats.cpu_time_percentile(50.0), 30.0);
+        assert_eq!(stats.cpu_time_percentile(90.0), 40.0);
+    }
+
+    #[test]
+    fn window_keeps_most_recent_samples() {
+        let mut stats = FrameStats::new(4);
+        for ms in 1..=6 { stats.push_frame_time(ms as f32); }
+
+        // 只保留 3、4、5、6
+        assert_eq!(stats.frame_time(), 4.5);
+        assert_eq!(stats.frame_time_percentile(0.0), 3.0);
+        assert_eq!(stats.frame_time_percentile(100.0), 6.0);
+        assert!((stats.fps() - 4.0 * 1000.0 / 18.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn empty_stats_are_zero() {
+        let mut stats = FrameStats::new(4);
+        assert_eq!(stats.fps(), 0.0);
+        assert_eq!(stats.gpu_time(), 0.0);
+        assert_eq!(stats.gpu_time_percentile(99.0), 0.0);
+
+        stats.push_gpu_time(2.0);
+        stats.clear();
+        assert_eq!(stats.gpu_time(), 0.0);
+    }
+}
@@ -0,0 +1,229 @@
+#![allow(dead_code)]
+
+use nalgebra_glm as glm;
+
+pub use opengl_rs_derive::Std140;
+
+/**
+ * 按 std140 布局写入 uniform 缓冲的类型
+ * 标量按自身大小对齐，vec2 按 8 字节、vec3/vec4 按 16 字节对齐
+ * 数组、矩阵的每个元素（列）以及结构体都按 16 字节对齐
+ * 结构体可以使用 #[derive(Std140)]
+ *
+ * ```
+ * use nalgebra_glm as glm;
+ * use opengl_rs::base::std140::{self, Std140};
+ *
+ * // layout (std140) uniform Light { vec3 position; float intensity; mat3 basis; };
+ * #[derive(Std140)]
+ * struct Light {
+ *     position: glm::Vec3,
+ *     intensity: f32,
+ *     basis: glm::Mat3,
+ * }
+ *
+ * assert_eq!(Light::member_offsets(), vec![("position", 0), ("intensity", 12), ("basis", 16)]);
+ * assert_eq!(std140::to_bytes(&Light { position: glm::Vec3::zeros(), intensity: 1.0, basis: glm::identity() }).len(), Light::SIZE);
+ * ```
+ */
+pub trait Std140 {
+    const ALIGN: usize;
+    const SIZE: usize;
+
+    // out 的长度为 SIZE
+    fn write_std140(&self, out: &mut [u8]);
+
+    // 结构体各成员的偏移，用于与着色器中的 uniform 块比较
+    fn member_offsets() -> Vec<(&'static str, usize)> { Vec::new() }
+}
+
+pub const fn align_up(offset: usize, align: usize) -> usize { offset.div_ceil(align) * align }
+
+pub const fn max(a: usize, b: usize) -> usize { if a > b { a } else { b } }
+
+// 按 std140 布局序列化
+pub fn to_bytes<T: Std140>(value: &T) -> Vec<u8> {
+    let mut bytes = vec![0; T::SIZE];
+    value.write_std140(&mut bytes);
+    bytes
+}
+
+// 向量、矩阵的分量类型
+trait Scalar: Copy { fn bytes(self) -> [u8; 4]; }
+
+impl Scalar for f32 { fn bytes(self) -> [u8; 4] { self.to_ne_bytes() } }
+impl Scalar for i32 { fn bytes(self) -> [u8; 4] { self.to_ne_bytes() } }
+impl Scalar for u32 { fn bytes(self) -> [u8; 4] { self.to_ne_bytes() } }
+
+// 每 count 个分量为一列，列与列之间相隔 stride 字节
+fn write_scalars<S: Scalar>(values: &[S], out: &mut [u8], stride: usize, count: usize) {
+    for (i, column) in values.chunks(count).enumerate() {
+        for (j, value) in column.iter().enumerate() {
+            let offset = i * stride + j * 4;
+            out[offset..offset + 4].copy_from_slice(&value.bytes());
+        }
+    }
+}
+
+macro_rules! impl_std140 {
+    ($type:ty, $scalar:ty, $align:expr, $size:expr, $stride:expr, $count:expr) => {
+        impl Std140 for $type {
+            const ALIGN: usize = $align;
+            const SIZE: usize = $size;
+
+            fn write_std140(&self, out: &mut [u8]) {
+                let values: &[$scalar] = self.as_slice();
+                write_scalars(values, out, $stride, $count);
+            }
+        }
+    };
+}
+
+impl Std140 for f32 {
+    const ALIGN: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write_std140(&self, out: &mut [u8]) { out[..4].copy_from_slice(&self.to_ne_bytes()); }
+}
+
+impl Std140 for i32 {
+    const ALIGN: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write_std140(&self, out: &mut [u8]) { out[..4].copy_from_slice(&self.to_ne_bytes()); }
+}
+
+impl Std140 for u32 {
+    const ALIGN: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write_std140(&self, out: &mut [u8]) { out[..4].copy_from_slice(&self.to_ne_bytes()); }
+}
+
+// GLSL 的 bool 占 4 字节
+impl Std140 for bool {
+    const ALIGN: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write_std140(&self, out: &mut [u8]) { (*self as u32).write_std140(out); }
+}
+
+// 向量：(类型, 分量类型, 对齐, 大小, 列间距, 每列分量数)
+impl_std140!(glm::Vec2, f32, 8, 8, 8, 2);
+impl_std140!(glm::Vec3, f32, 16, 12, 12, 3);
+impl_std140!(glm::Vec4, f32, 16, 16, 16, 4);
+impl_std140!(glm::IVec2, i32, 8, 8, 8, 2);
+impl_std140!(glm::IVec3, i32, 16, 12, 12, 3);
+impl_std140!(glm::IVec4, i32, 16, 16, 16, 4);
+impl_std140!(glm::UVec2, u32, 8, 8, 8, 2);
+impl_std140!(glm::UVec3, u32, 16, 12, 12, 3);
+impl_std140!(glm::UVec4, u32, 16, 16, 16, 4);
+
+// 矩阵按列存储，每列按 vec4 对齐
+impl_std140!(glm::Mat2, f32, 16, 32, 16, 2);
+impl_std140!(glm::Mat3, f32, 16, 48, 16, 3);
+impl_std140!(glm::Mat4, f32, 16, 64, 16, 4);
+
+impl<T: Std140, const N: usize> Std140 for [T; N] {
+    const ALIGN: usize = align_up(T::ALIGN, 16);
+    const SIZE: usize = align_up(T::SIZE, Self::ALIGN) * N;
+
+    fn write_std140(&self, out: &mut [u8]) {
+        let stride = align_up(T::SIZE, Self::ALIGN);
+        for (i, value) in self.iter().enumerate() {
+            value.write_std140(&mut out[i * stride..i * stride + T::SIZE]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::camera::CameraBlock;
+
+    // layout (std140) uniform Mixed { vec3 a; float b; mat3 m; float arr[3]; vec2 v2s[2]; float c; };
+    #[derive(Std140)]
+    struct Mixed {
+        a: glm::Vec3,
+        b: f32,
+        m: glm::Mat3,
+        arr: [f32; 3],
+        v2s: [glm::Vec2; 2],
+        c: f32,
+    }
+
+    #[derive(Std140)]
+    struct Inner { x: f32 }
+
+    // 嵌套的结构体按 16 字节对齐，跳过的字段不占空间
+    #[derive(Std140)]
+    struct Nested {
+        f: f32,
+        inner: Inner,
+        #[uniform(skip)]
+        _skipped: glm::Mat4,
+        g: f32,
+    }
+
+    fn f32_at(bytes: &[u8], offset: usize) -> f32 { f32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap()) }
+
+    #[test]
+    fn scalar_vector_and_matrix_sizes() {
+        assert_eq!((f32::ALIGN, f32::SIZE), (4, 4));
+        assert_eq!((bool::ALIGN, bool::SIZE), (4, 4));
+        assert_eq!((glm::Vec2::ALIGN, glm::Vec2::SIZE), (8, 8));
+        assert_eq!((glm::Vec3::ALIGN, glm::Vec3::SIZE), (16, 12));
+        assert_eq!((glm::Vec4::ALIGN, glm::Vec4::SIZE), (16, 16));
+        assert_eq!((glm::Mat3::ALIGN, glm::Mat3::SIZE), (16, 48));
+        assert_eq!((glm::Mat4::ALIGN, glm::Mat4::SIZE), (16, 64));
+        assert_eq!((<[f32; 3]>::ALIGN, <[f32; 3]>::SIZE), (16, 48));
+        assert_eq!((<[glm::Vec4; 2]>::ALIGN, <[glm::Vec4; 2]>::SIZE), (16, 32));
+    }
+
+    #[test]
+    fn camera_block_layout() {
+        assert_eq!(CameraBlock::ALIGN, 16);
+        assert_eq!(CameraBlock::SIZE, 144);
+        assert_eq!(CameraBlock::member_offsets(), vec![("view", 0), ("projection", 64), ("viewPos", 128)]);
+    }
+
+    // 偏移与 Mesa 对同一个 uniform 块反射得到的结果一致
+    #[test]
+    fn mixed_block_layout() {
+        assert_eq!(Mixed::SIZE, 160);
+        assert_eq!(Mixed::member_offsets(), vec![("a", 0), ("b", 12), ("m", 16), ("arr", 64), ("v2s", 112), ("c", 144)]);
+    }
+
+    #[test]
+    fn nested_block_layout() {
+        assert_eq!(Inner::SIZE, 16);
+        assert_eq!(Nested::SIZE, 48);
+        assert_eq!(Nested::member_offsets(), vec![("f", 0), ("inner", 16), ("g", 32)]);
+    }
+
+    #[test]
+    fn writes_values_at_their_offsets() {
+        let value = Mixed {
+            a: glm::vec3(1.0, 2.0, 3.0),
+            b: 4.0,
+            m: glm::mat3(5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0),
+            arr: [14.0, 15.0, 16.0],
+            v2s: [glm::vec2(17.0, 18.0), glm::vec2(19.0, 20.0)],
+            c: 21.0,
+        };
+        let bytes = to_bytes(&value);
+        assert_eq!(bytes.len(), 160);
+
+        // vec3 后的 float 紧跟在第 12 字节
+        assert_eq!([0, 4, 8, 12].map(|offset| f32_at(&bytes, offset)), [1.0, 2.0, 3.0, 4.0]);
+
+        // mat3 按列存储，每列占 16 字节，第 4 个分量为填充
+        assert_eq!([16, 20, 24, 28].map(|offset| f32_at(&bytes, offset)), [5.0, 8.0, 11.0, 0.0]);
+        assert_eq!([32, 48].map(|offset| f32_at(&bytes, offset)), [6.0, 7.0]);
+
+        // 数组元素间隔 16 字节
+        assert_eq!([64, 80, 96].map(|offset| f32_at(&bytes, offset)), [14.0, 15.0, 16.0]);
+        assert_eq!([112, 116, 128, 132].map(|offset| f32_at(&bytes, offset)), [17.0, 18.0, 19.0, 20.0]);
+        assert_eq!(f32_at(&bytes, 144), 21.0);
+    }
+}
@@ -34,7 +34,9 @@ impl Texture {
             let mut ret = Self { id: 0, path: path.into(), wrap_s_mode, wrap_t_mode, min_filter_mode, mag_filter_mode };
 
             gl::GenTextures(1, &mut ret.id);
-    
+
+            // 环绕、过滤方式与图像数据都作用于当前绑定的纹理
+            ret.bind();
             ret.set_wrapping_filtering();
 
             ret.load()?;
@@ -57,6 +59,7 @@ impl Texture {
             let path = Path::new(&self.path);
 
             let img = image::open(path)?.flipv();
+            self.bind();
 
             let format = match img {
                 DynamicImage::ImageLuma8(_) => gl::RED,
@@ -0,0 +1,269 @@
+#![allow(dead_code)]
+
+use std::ffi::CString;
+
+use gl::types::{GLenum, GLint, GLuint, GLsizei, GLchar};
+use nalgebra_glm as glm;
+
+use crate::base::{error::ShaderError, program::ShaderProgram};
+
+pub use opengl_rs_derive::Uniforms;
+
+/**
+ * 程序中活动的 uniform 变量
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniformInfo {
+    pub name: String,                           // 数组以 "name[0]" 的形式给出
+    pub gl_type: GLenum,
+    pub size: i32,                              // 数组长度，非数组为 1
+    pub location: GLint,                        // uniform 块中的变量为 -1
+}
+
+/**
+ * 程序中活动的顶点属性
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeInfo {
+    pub name: String,
+    pub gl_type: GLenum,
+    pub size: i32,
+    pub location: GLint,
+}
+
+/**
+ * 程序中活动的 uniform 块
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniformBlockInfo {
+    pub name: String,
+    pub index: GLuint,
+    pub binding: GLuint,                        // 绑定点
+    pub size: usize,                            // 块的数据大小
+    pub members: Vec<(String, usize)>,          // 成员名及其偏移
+}
+
+// 链接后读取所有活动的 uniform 块及其成员的偏移
+pub(crate) unsafe fn reflect_uniform_blocks(program: GLuint, uniforms: &[UniformInfo]) -> Vec<UniformBlockInfo> {
+    let (mut count, mut max_length): (GLint, GLint) = (0, 0);
+    gl::GetProgramiv(program, gl::ACTIVE_UNIFORM_BLOCKS, &mut count);
+    gl::GetProgramiv(program, gl::ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH, &mut max_length);
+
+    // 每个 uniform 变量所属的块及其偏移，不在块中的为 -1
+    let indices: Vec<GLuint> = (0..uniforms.len() as GLuint).collect();
+    let mut block_indices: Vec<GLint> = vec![-1; uniforms.len()];
+    let mut offsets: Vec<GLint> = vec![-1; uniforms.len()];
+    if !uniforms.is_empty() {
+        gl::GetActiveUniformsiv(program, indices.len() as GLsizei, indices.as_ptr(), gl::UNIFORM_BLOCK_INDEX, block_indices.as_mut_ptr());
+        gl::GetActiveUniformsiv(program, indices.len() as GLsizei, indices.as_ptr(), gl::UNIFORM_OFFSET, offsets.as_mut_ptr());
+    }
+
+    let mut buffer: Vec<u8> = vec![0; max_length.max(1) as usize];
+    (0..count as GLuint).map(|index| {
+        let mut length: GLsizei = 0;
+        gl::GetActiveUniformBlockName(program, index, buffer.len() as GLsizei, &mut length, buffer.as_mut_ptr().cast());
+        let name = String::from_utf8_lossy(&buffer[..length as usize]).into_owned();
+
+        let (mut binding, mut size): (GLint, GLint) = (0, 0);
+        gl::GetActiveUniformBlockiv(program, index, gl::UNIFORM_BLOCK_BINDING, &mut binding);
+        gl::GetActiveUniformBlockiv(program, index, gl::UNIFORM_BLOCK_DATA_SIZE, &mut size);
+
+        let members = uniforms.iter().zip(block_indices.iter().zip(&offsets))
+            .filter(|(_, (block, _))| **block == index as GLint)
+            .map(|(info, (_, offset))| (info.name.clone(), *offset as usize))
+            .collect();
+
+        UniformBlockInfo { name, index, binding: binding as GLuint, size: size as usize, members }
+    }).collect()
+}
+
+// 链接后读取所有活动的 uniform 变量
+pub(crate) unsafe fn reflect_uniforms(program: GLuint) -> Vec<UniformInfo> {
+    reflect(program, gl::ACTIVE_UNIFORMS, gl::ACTIVE_UNIFORM_MAX_LENGTH, gl::GetActiveUniform, gl::GetUniformLocation)
+        .into_iter()
+        .map(|(name, gl_type, size, location)| UniformInfo { name, gl_type, size, location })
+        .collect()
+}
+
+// 链接后读取所有活动的顶点属性
+pub(crate) unsafe fn reflect_attributes(program: GLuint) -> Vec<AttributeInfo> {
+    reflect(program, gl::ACTIVE_ATTRIBUTES, gl::ACTIVE_ATTRIBUTE_MAX_LENGTH, gl::GetActiveAttrib, gl::GetAttribLocation)
+        .into_iter()
+        .map(|(name, gl_type, size, location)| AttributeInfo { name, gl_type, size, location })
+        .collect()
+}
+
+type GetActive = unsafe fn(GLuint, GLuint, GLsizei, *mut GLsizei, *mut GLint, *mut GLenum, *mut GLchar);
+type GetLocation = unsafe fn(GLuint, *const GLchar) -> GLint;
+
+unsafe fn reflect(program: GLuint, count_param: GLenum, length_param: GLenum, get_active: GetActive, get_location: GetLocation) -> Vec<(String, GLenum, i32, GLint)> {
+    let (mut count, mut max_length): (GLint, GLint) = (0, 0);
+    gl::GetProgramiv(program, count_param, &mut count);
+    gl::GetProgramiv(program, length_param, &mut max_length);
+
+    let mut buffer: Vec<u8> = vec![0; max_length.max(1) as usize];
+    (0..count as GLuint).map(|i| {
+        let (mut length, mut size, mut gl_type): (GLsizei, GLint, GLenum) = (0, 0, 0);
+        get_active(program, i, buffer.len() as GLsizei, &mut length, &mut size, &mut gl_type, buffer.as_mut_ptr().cast());
+
+        let name = String::from_utf8_lossy(&buffer[..length as usize]).into_owned();
+        let location = CString::new(name.as_str()).map_or(-1, |cs| get_location(program, cs.as_ptr()));
+
+        (name, gl_type, size, location)
+    }).collect()
+}
+
+// GLSL 类型名，用于错误信息
+pub fn gl_type_name(gl_type: GLenum) -> &'static str {
+    match gl_type {
+        gl::FLOAT => "float",
+        gl::FLOAT_VEC2 => "vec2",
+        gl::FLOAT_VEC3 => "vec3",
+        gl::FLOAT_VEC4 => "vec4",
+        gl::INT => "int",
+        gl::INT_VEC2 => "ivec2",
+        gl::INT_VEC3 => "ivec3",
+        gl::INT_VEC4 => "ivec4",
+        gl::UNSIGNED_INT => "uint",
+        gl::UNSIGNED_INT_VEC2 => "uvec2",
+        gl::UNSIGNED_INT_VEC3 => "uvec3",
+        gl::UNSIGNED_INT_VEC4 => "uvec4",
+        gl::BOOL => "bool",
+        gl::BOOL_VEC2 => "bvec2",
+        gl::BOOL_VEC3 => "bvec3",
+        gl::BOOL_VEC4 => "bvec4",
+        gl::FLOAT_MAT2 => "mat2",
+        gl::FLOAT_MAT3 => "mat3",
+        gl::FLOAT_MAT4 => "mat4",
+        gl::FLOAT_MAT2x3 => "mat2x3",
+        gl::FLOAT_MAT2x4 => "mat2x4",
+        gl::FLOAT_MAT3x2 => "mat3x2",
+        gl::FLOAT_MAT3x4 => "mat3x4",
+        gl::FLOAT_MAT4x2 => "mat4x2",
+        gl::FLOAT_MAT4x3 => "mat4x3",
+        gl::SAMPLER_1D => "sampler1D",
+        gl::SAMPLER_2D => "sampler2D",
+        gl::SAMPLER_3D => "sampler3D",
+        gl::SAMPLER_CUBE => "samplerCube",
+        gl::SAMPLER_2D_SHADOW => "sampler2DShadow",
+        gl::SAMPLER_2D_ARRAY => "sampler2DArray",
+        gl::SAMPLER_2D_MULTISAMPLE => "sampler2DMS",
+        gl::INT_SAMPLER_2D => "isampler2D",
+        gl::UNSIGNED_INT_SAMPLER_2D => "usampler2D",
+        _ => "unknown",
+    }
+}
+
+// 采样器类型通过 int 设置纹理单元
+pub fn is_sampler(gl_type: GLenum) -> bool {
+    matches!(gl_type,
+        gl::SAMPLER_1D | gl::SAMPLER_2D | gl::SAMPLER_3D | gl::SAMPLER_CUBE |
+        gl::SAMPLER_1D_SHADOW | gl::SAMPLER_2D_SHADOW | gl::SAMPLER_CUBE_SHADOW |
+        gl::SAMPLER_1D_ARRAY | gl::SAMPLER_2D_ARRAY | gl::SAMPLER_1D_ARRAY_SHADOW | gl::SAMPLER_2D_ARRAY_SHADOW |
+        gl::SAMPLER_2D_MULTISAMPLE | gl::SAMPLER_2D_MULTISAMPLE_ARRAY | gl::SAMPLER_BUFFER | gl::SAMPLER_2D_RECT |
+        gl::INT_SAMPLER_2D | gl::INT_SAMPLER_3D | gl::INT_SAMPLER_CUBE | gl::INT_SAMPLER_2D_ARRAY |
+        gl::UNSIGNED_INT_SAMPLER_2D | gl::UNSIGNED_INT_SAMPLER_3D | gl::UNSIGNED_INT_SAMPLER_CUBE | gl::UNSIGNED_INT_SAMPLER_2D_ARRAY
+    )
+}
+
+/**
+ * 可以上传到 uniform 变量的 Rust 类型
+ */
+pub trait UniformValue: Sized {
+    // Rust 类型名，用于错误信息
+    const RUST_TYPE: &'static str;
+
+    // 是否可以设置给该 GLSL 类型的变量
+    fn accepts(gl_type: GLenum) -> bool;
+
+    // 从 location 开始上传 values.len() 个元素
+    unsafe fn upload(location: GLint, values: &[Self]);
+}
+
+macro_rules! impl_uniform_value {
+    ($type:ty, $name:expr, [$($gl_type:path),+], |$location:ident, $count:ident, $ptr:ident| $upload:expr) => {
+        impl UniformValue for $type {
+            const RUST_TYPE: &'static str = $name;
+
+            fn accepts(gl_type: GLenum) -> bool { matches!(gl_type, $($gl_type)|+) }
+
+            unsafe fn upload($location: GLint, values: &[Self]) {
+                let $count = values.len() as GLsizei;
+                let $ptr = values.as_ptr().cast();
+                $upload
+            }
+        }
+    };
+}
+
+impl_uniform_value!(f32, "f32", [gl::FLOAT], |l, n, p| gl::Uniform1fv(l, n, p));
+impl_uniform_value!(glm::Vec2, "Vec2", [gl::FLOAT_VEC2], |l, n, p| gl::Uniform2fv(l, n, p));
+impl_uniform_value!(glm::Vec3, "Vec3", [gl::FLOAT_VEC3], |l, n, p| gl::Uniform3fv(l, n, p));
+impl_uniform_value!(glm::Vec4, "Vec4", [gl::FLOAT_VEC4], |l, n, p| gl::Uniform4fv(l, n, p));
+impl_uniform_value!(glm::IVec2, "IVec2", [gl::INT_VEC2, gl::BOOL_VEC2], |l, n, p| gl::Uniform2iv(l, n, p));
+impl_uniform_value!(glm::IVec3, "IVec3", [gl::INT_VEC3, gl::BOOL_VEC3], |l, n, p| gl::Uniform3iv(l, n, p));
+impl_uniform_value!(glm::IVec4, "IVec4", [gl::INT_VEC4, gl::BOOL_VEC4], |l, n, p| gl::Uniform4iv(l, n, p));
+impl_uniform_value!(u32, "u32", [gl::UNSIGNED_INT, gl::BOOL], |l, n, p| gl::Uniform1uiv(l, n, p));
+impl_uniform_value!(glm::UVec2, "UVec2", [gl::UNSIGNED_INT_VEC2], |l, n, p| gl::Uniform2uiv(l, n, p));
+impl_uniform_value!(glm::UVec3, "UVec3", [gl::UNSIGNED_INT_VEC3], |l, n, p| gl::Uniform3uiv(l, n, p));
+impl_uniform_value!(glm::UVec4, "UVec4", [gl::UNSIGNED_INT_VEC4], |l, n, p| gl::Uniform4uiv(l, n, p));
+impl_uniform_value!(glm::Mat2, "Mat2", [gl::FLOAT_MAT2], |l, n, p| gl::UniformMatrix2fv(l, n, gl::FALSE, p));
+impl_uniform_value!(glm::Mat3, "Mat3", [gl::FLOAT_MAT3], |l, n, p| gl::UniformMatrix3fv(l, n, gl::FALSE, p));
+impl_uniform_value!(glm::Mat4, "Mat4", [gl::FLOAT_MAT4], |l, n, p| gl::UniformMatrix4fv(l, n, gl::FALSE, p));
+
+// int 同时用于设置 bool 与采样器
+impl UniformValue for i32 {
+    const RUST_TYPE: &'static str = "i32";
+
+    fn accepts(gl_type: GLenum) -> bool { matches!(gl_type, gl::INT | gl::BOOL) || is_sampler(gl_type) }
+
+    unsafe fn upload(location: GLint, values: &[Self]) { gl::Uniform1iv(location, values.len() as GLsizei, values.as_ptr()); }
+}
+
+impl UniformValue for bool {
+    const RUST_TYPE: &'static str = "bool";
+
+    fn accepts(gl_type: GLenum) -> bool { gl_type == gl::BOOL }
+
+    unsafe fn upload(location: GLint, values: &[Self]) {
+        let values: Vec<i32> = values.iter().map(|v| *v as i32).collect();
+        gl::Uniform1iv(location, values.len() as GLsizei, values.as_ptr());
+    }
+}
+
+/**
+ * 可以整体上传的结构体，通常由 #[derive(Uniforms)] 实现
+ * prefix: GLSL 中结构体变量的名字，字段以 "prefix.field" 的形式上传
+ *
+ * ```no_run
+ * use nalgebra_glm as glm;
+ * use opengl_rs::base::{error::ShaderError, program::ShaderProgram, uniform::Uniforms};
+ *
+ * // struct Light { vec3 position; vec3 color; };
+ * #[derive(Uniforms)]
+ * struct Light {
+ *     position: glm::Vec3,
+ *     color: glm::Vec3,
+ * }
+ *
+ * // struct Scene { Light lights[2]; float weights[4]; int lightCount; };
+ * #[derive(Uniforms)]
+ * struct Scene {
+ *     #[uniform(nested)]
+ *     lights: [Light; 2],
+ *     weights: Vec<f32>,
+ *     #[uniform(rename = "lightCount")]
+ *     light_count: i32,
+ *     #[uniform(skip)]
+ *     name: String,
+ * }
+ *
+ * // 依次设置 scene.lights[0].position、scene.lights[0].color、...、scene.weights、scene.lightCount
+ * unsafe fn upload(program: &ShaderProgram, scene: &Scene) -> Result<(), ShaderError> {
+ *     program.set_uniforms("scene", scene)
+ * }
+ * ```
+ */
+pub trait Uniforms {
+    unsafe fn set_uniforms(&self, program: &ShaderProgram, prefix: &str) -> Result<(), ShaderError>;
+}
@@ -0,0 +1,159 @@
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use gl::types::{GLint, GLuint};
+
+use crate::base::buffer::Buffer;
+use crate::base::error::ShaderError;
+use crate::base::program::ShaderProgram;
+use crate::base::std140::{self, Std140};
+use crate::base::uniform::UniformBlockInfo;
+
+// UniformBuffer 的布局，程序链接时用来检查同名的 uniform 块
+#[derive(Debug, Clone, PartialEq)]
+struct BlockLayout {
+    size: usize,
+    members: Vec<(&'static str, usize)>,
+}
+
+thread_local! {
+    // uniform 块名到绑定点的映射，OpenGL 上下文只在创建它的线程中使用
+    static BINDING_POINTS: RefCell<HashMap<String, GLuint>> = RefCell::new(HashMap::new());
+    // 块名到布局以及使用该布局的缓冲个数
+    static LAYOUTS: RefCell<HashMap<String, (BlockLayout, usize)>> = RefCell::new(HashMap::new());
+}
+
+/**
+ * uniform 块对应的绑定点，第一次使用时分配
+ * ShaderProgram 链接时会把同名的块绑定到这里，所以程序与缓冲的创建顺序无关
+ * 不同名字的块超过 GL_MAX_UNIFORM_BUFFER_BINDINGS 个时返回错误
+ */
+pub unsafe fn binding_point(block_name: &str) -> Result<GLuint, ShaderError> {
+    BINDING_POINTS.with(|points| {
+        let mut points = points.borrow_mut();
+        if let Some(binding) = points.get(block_name) { return Ok(*binding); }
+
+        let mut max_bindings: GLint = 0;
+        gl::GetIntegerv(gl::MAX_UNIFORM_BUFFER_BINDINGS, &mut max_bindings);
+
+        let next = points.len() as GLuint;
+        if next >= max_bindings as GLuint {
+            return Err(ShaderError::TooManyUniformBlocks(block_name.into(), max_bindings as u32));
+        }
+        points.insert(block_name.into(), next);
+        Ok(next)
+    })
+}
+
+// 检查程序中的块与同名 UniformBuffer 的布局是否一致，还没有创建同名的缓冲时不检查
+pub(crate) fn validate_block(block: &UniformBlockInfo) -> Result<(), ShaderError> {
+    LAYOUTS.with(|layouts| match layouts.borrow().get(&block.name) {
+        Some((layout, _)) => check_layout(block, layout.size, &layout.members),
+        None => Ok(()),
+    })
+}
+
+/**
+ * 块中的成员偏移需要与缓冲的成员偏移相同，并且块的大小不能超过缓冲
+ * 数组成员以 "name[0]" 的形式出现，结构体成员以 "a.b" 的形式出现，只比较最后一段
+ */
+fn check_layout(block: &UniformBlockInfo, size: usize, members: &[(&str, usize)]) -> Result<(), ShaderError> {
+    let mismatch = |reason: String| Err(ShaderError::UniformBlockMismatch(block.name.clone(), reason));
+
+    if block.size > size {
+        return mismatch(format!("the block has {} bytes but the buffer only has {}", block.size, size));
+    }
+
+    for (member, offset) in members {
+        let found = block.members.iter().find(|(name, _)| {
+            let name = name.rsplit_once('.').map_or(name.as_str(), |(_, name)| name);
+            name == *member || name.strip_suffix("[0]") == Some(*member)
+        });
+        if let Some((name, block_offset)) = found {
+            if block_offset != offset {
+                return mismatch(format!("{} is at offset {} in the block but {} in the buffer", name, block_offset, offset));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * 按 std140 布局存储 T 的 uniform 缓冲，绑定到与 uniform 块同名的绑定点
+ */
+pub struct UniformBuffer<T: Std140> {
+    buffer: Buffer,
+    block_name: String,
+    binding: GLuint,
+    data: Vec<u8>,                              // 按 std140 布局序列化后的数据
+
+    _marker: PhantomData<T>,
+}
+
+impl<T: Std140> UniformBuffer<T> {
+    /**
+     * block_name: 着色器中 uniform 块的名字，如 layout (std140) uniform Camera { ... } 中的 Camera
+     * 之后链接的程序中同名的块会按 T 的布局检查，不一致时程序创建失败；已经链接的程序可以用 validate 检查
+     */
+    pub unsafe fn new(block_name: &str, value: &T) -> Result<Self, ShaderError> {
+        let binding = binding_point(block_name)?;
+        let data = std140::to_bytes(value);
+        let buffer = Buffer::new(gl::UNIFORM_BUFFER, data.as_slice(), gl::DYNAMIC_DRAW);
+        let ret = Self { buffer, block_name: block_name.into(), binding, data, _marker: PhantomData };
+
+        // 同名的缓冲布局不同时以新缓冲为准
+        LAYOUTS.with(|layouts| {
+            let mut layouts = layouts.borrow_mut();
+            match layouts.get_mut(block_name) {
+                Some((layout, count)) if *layout == Self::layout() => *count += 1,
+                _ => { layouts.insert(block_name.into(), (Self::layout(), 1)); },
+            }
+        });
+
+        ret.bind();
+        Ok(ret)
+    }
+
+    fn layout() -> BlockLayout { BlockLayout { size: T::SIZE, members: T::member_offsets() } }
+
+    pub fn block_name(&self) -> &str { &self.block_name }
+
+    pub fn binding(&self) -> GLuint { self.binding }
+
+    // 更新缓冲中的数据，所有使用该块的程序都会看到新的值
+    pub unsafe fn update(&mut self, value: &T) {
+        value.write_std140(&mut self.data);
+        self.buffer.set_sub_data(0, self.data.as_slice());
+    }
+
+    // 绑定到绑定点，创建时已经绑定，只有绑定点被其他缓冲占用后才需要再次调用
+    pub unsafe fn bind(&self) {
+        gl::BindBufferBase(gl::UNIFORM_BUFFER, self.binding, self.buffer.id);
+    }
+
+    // 检查程序中同名的 uniform 块与 T 的布局是否一致，规则见 check_layout
+    pub fn validate(&self, program: &ShaderProgram) -> Result<(), ShaderError> {
+        let block = program.uniform_block(&self.block_name)
+            .ok_or_else(|| ShaderError::UniformBlockMismatch(self.block_name.clone(), "the block is not active in the program".into()))?;
+
+        check_layout(block, T::SIZE, &T::member_offsets())
+    }
+}
+
+// 同名同布局的缓冲全部释放后不再检查同名块的布局，绑定点保留，已链接的程序仍然绑定在原来的绑定点
+impl<T: Std140> Drop for UniformBuffer<T> {
+    fn drop(&mut self) {
+        LAYOUTS.with(|layouts| {
+            let mut layouts = layouts.borrow_mut();
+            let Some((layout, count)) = layouts.get_mut(&self.block_name) else { return };
+            if *layout != Self::layout() { return; }
+
+            *count -= 1;
+            if *count == 0 { layouts.remove(&self.block_name); }
+        });
+    }
+}
@@ -2,6 +2,7 @@
 #![allow(dead_code)]
 
 use gl::types::{GLuint, GLint};
+use image::RgbaImage;
 
 use crate::base::error::ShaderError;
 
@@ -47,4 +48,32 @@ pub unsafe fn check_compile_error(id: GLuint, _type: CheckType) -> Result<(), Sh
 
         Err(ShaderError::LinkingError(log))
     }
+}
+
+// 当前上下文的 OpenGL 版本 (major, minor)
+pub unsafe fn context_version() -> (u32, u32) {
+    let (mut major, mut minor): (GLint, GLint) = (0, 0);
+    gl::GetIntegerv(gl::MAJOR_VERSION, &mut major);
+    gl::GetIntegerv(gl::MINOR_VERSION, &mut minor);
+
+    (major as u32, minor as u32)
+}
+
+// 读取当前绑定的帧缓冲的像素，并翻转为图像坐标系（原点在左上角）
+pub unsafe fn read_pixels(size: (u32, u32)) -> RgbaImage {
+    let mut pixels: Vec<u8> = vec![0; (size.0 * size.1 * 4) as usize];
+
+    gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+    gl::ReadPixels(
+        0, 
+        0, 
+        size.0 as i32, 
+        size.1 as i32, 
+        gl::RGBA, 
+        gl::UNSIGNED_BYTE, 
+        pixels.as_mut_ptr() as *mut _
+    );
+
+    let img = RgbaImage::from_raw(size.0, size.1, pixels).unwrap();
+    image::imageops::flip_vertical(&img)
 }
\ No newline at end of file
@@ -2,6 +2,9 @@
 #![allow(dead_code)]
 #![allow(clippy::missing_safety_doc)]
 
+// 派生宏生成的代码以 ::opengl_rs 引用本库
+extern crate self as opengl_rs;
+
 pub mod base;
 pub mod advance;
 
@@ -10,19 +13,57 @@ use std::rc::Rc;
 
 use base::error::GLError;
 use base::camera::Camera;
+use base::input::InputState;
+use glfw::{Key, MouseButton, Action, Modifiers};
 
 pub trait IRenderer: Sized {
+    // 着色器是否使用引擎每帧更新的 Camera uniform 块（glsl/include/camera.glsl），为 false 时引擎不创建该块的缓冲，也不检查同名块的布局
+    const USES_CAMERA_BLOCK: bool = false;
+
     // 绘制前处理
     unsafe fn pre_draw(&self) -> Result<(), GLError> { Ok(()) }
     // 绘制
     unsafe fn draw(&self) -> Result<(), GLError>;
 
+    /**
+     * 引擎每帧调用的绘制入口
+     * alpha: 距上一次 update 经过的时间占固定步长的比例，范围 [0, 1)，可用于在两次 update 的状态之间插值
+     */
+    unsafe fn draw_interpolated(&self, _alpha: f32) -> Result<(), GLError> { self.draw() }
+
+    // 以固定的时间步长更新状态，一帧内可能调用零次或多次
+    fn update(&mut self, _dt: f32) { }
+
     // 开启深度测试
     fn enable_deep_test(&mut self) { }
 
     // 清屏
     unsafe fn clear(&self) { }
 
+    // 重新加载源文件有修改的着色器，开启热重载时引擎会定期调用
+    unsafe fn reload_shaders(&mut self) { }
+
     // 获取摄像机
     fn getCamera(&self) -> Option<Rc<RefCell<Camera>>> { None }
+
+    // 帧缓冲大小改变，创建引擎后也会以初始大小调用一次
+    fn on_resize(&mut self, _size: (u32, u32)) { }
+
+    // 键盘事件
+    fn on_key(&mut self, _key: Key, _action: Action, _mods: Modifiers) { }
+
+    // 鼠标按键事件
+    fn on_mouse_button(&mut self, _button: MouseButton, _action: Action, _mods: Modifiers) { }
+
+    // 鼠标移动事件，坐标以窗口左上角为原点
+    fn on_cursor_move(&mut self, _x: f32, _y: f32) { }
+
+    // 鼠标滚轮事件
+    fn on_scroll(&mut self, _x_offset: f32, _y_offset: f32) { }
+
+    // 文本输入事件
+    fn on_char(&mut self, _c: char) { }
+
+    // 每帧处理完事件后、绘制前调用，可查询当前的输入状态
+    fn on_input(&mut self, _input: &InputState) { }
 }
\ No newline at end of file
@@ -0,0 +1,138 @@
+// 基准图像测试，见 README 的“基准图像测试”一节
+// glfw 初始化需要 X11 或 Wayland 显示，OPENGL_RS_CONTEXT_API=egl/osmesa 也不例外，没有显示设备的 CI 上用 xvfb-run -a cargo test 运行
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Mutex;
+
+use opengl_rs::IRenderer;
+use opengl_rs::advance::cube::Cube;
+use opengl_rs::advance::sphere::Sphere;
+use opengl_rs::base::camera::Camera;
+use opengl_rs::base::error::GLError;
+use opengl_rs::base::golden::GoldenTest;
+use opengl_rs::base::model::Model;
+use opengl_rs::base::program::ShaderProgram;
+use opengl_rs::base::uniform::Uniforms;
+
+use nalgebra_glm as glm;
+
+const SIZE: (u32, u32) = (256, 256);
+
+// glfw 不能在多个线程中同时初始化，测试需要串行执行
+static GLFW_LOCK: Mutex<()> = Mutex::new(());
+
+struct Scene<T: IRenderer> {
+    inner: T,
+    camera: Rc<RefCell<Camera>>,
+}
+
+impl<T: IRenderer> IRenderer for Scene<T> {
+    const USES_CAMERA_BLOCK: bool = T::USES_CAMERA_BLOCK;
+
+    unsafe fn draw(&self) -> Result<(), GLError> {
+        gl::ClearColor(0.5, 0.5, 0.5, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+        self.inner.draw()
+    }
+
+    unsafe fn pre_draw(&self) -> Result<(), GLError> {
+        gl::Enable(gl::DEPTH_TEST);
+        Ok(())
+    }
+
+    fn getCamera(&self) -> Option<Rc<RefCell<Camera>>> { Some(Rc::clone(&self.camera)) }
+
+    fn on_resize(&mut self, size: (u32, u32)) { self.inner.on_resize(size); }
+}
+
+// 带有材质的冯氏光照，覆盖 glsl/phone_light_material/object.fs
+struct PhoneLightMaterial {
+    program: ShaderProgram,
+    model: Model,
+}
+
+#[derive(Uniforms)]
+struct Light {
+    position: glm::Vec3,
+    ambient: glm::Vec3,
+    diffuse: glm::Vec3,
+    specular: glm::Vec3,
+}
+
+#[derive(Uniforms)]
+struct Material {
+    ambient: glm::Vec3,
+    diffuse: glm::Vec3,
+    specular: glm::Vec3,
+    shininess: f32,
+}
+
+impl PhoneLightMaterial {
+    unsafe fn new() -> Result<Self, GLError> {
+        let program = ShaderProgram::new("glsl/phone_light_material/object.vs", "glsl/phone_light_material/object.fs")?;
+        let model = Model::new("assets/model/cube/cueb.obj", None)?;
+
+        Ok(Self { program, model })
+    }
+}
+
+impl IRenderer for PhoneLightMaterial {
+    const USES_CAMERA_BLOCK: bool = true;
+
+    unsafe fn draw(&self) -> Result<(), GLError> {
+        let light = Light {
+            position: glm::vec3(1.2, 1.0, 2.0),
+            ambient: glm::vec3(0.2, 0.2, 0.2),
+            diffuse: glm::vec3(0.5, 0.5, 0.5),
+            specular: glm::vec3(1.0, 1.0, 1.0),
+        };
+        let material = Material {
+            ambient: glm::vec3(1.0, 0.5, 0.31),
+            diffuse: glm::vec3(1.0, 0.5, 0.31),
+            specular: glm::vec3(0.5, 0.5, 0.5),
+            shininess: 32.0,
+        };
+        self.program.set_uniforms("light", &light)?;
+        self.program.set_uniforms("material", &material)?;
+
+        // 旋转后可以同时看到三个面，覆盖漫反射与镜面光照
+        let model = glm::rotate(&glm::Mat4::identity(), 0.6, &glm::vec3(1.0, 1.0, 0.0));
+        self.program.set_mat4("model", glm::value_ptr(&glm::scale(&model, &glm::vec3(0.6, 0.6, 0.6))))?;
+
+        self.model.draw(&self.program)
+    }
+}
+
+#[test]
+fn cube_matches_golden() -> Result<(), GLError> {
+    let _guard = GLFW_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    GoldenTest::new("tests/golden/cube.png", SIZE).run(|| unsafe {
+        let camera = Rc::new(RefCell::new(Camera::new(glm::vec3(0.0, 0.0, 4.0))));
+        let pos = vec![[0.0, 0.0, 0.0], [1.5, 0.2, -1.5]];
+        let inner = Cube::new(SIZE, ("assets/image/awesomeface.png", "assets/image/container.jpg"), pos, Rc::clone(&camera))?;
+        Ok(Scene { inner, camera })
+    })
+}
+
+#[test]
+fn sphere_matches_golden() -> Result<(), GLError> {
+    let _guard = GLFW_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    GoldenTest::new("tests/golden/sphere.png", SIZE).run(|| unsafe {
+        let camera = Rc::new(RefCell::new(Camera::new(glm::vec3(0.0, 0.0, 4.0))));
+        let inner = Sphere::new(SIZE, Rc::clone(&camera))?;
+        Ok(Scene { inner, camera })
+    })
+}
+
+
+#[test]
+fn phone_light_material_matches_golden() -> Result<(), GLError> {
+    let _guard = GLFW_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    GoldenTest::new("tests/golden/phone_light_material.png", SIZE).run(|| unsafe {
+        let camera = Rc::new(RefCell::new(Camera::new(glm::vec3(0.0, 0.0, 4.0))));
+        let inner = PhoneLightMaterial::new()?;
+        Ok(Scene { inner, camera })
+    })
+}